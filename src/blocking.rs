@@ -0,0 +1,185 @@
+//! Blocking variant of the high level driver, for targets without an async executor.
+//!
+//! Wraps the async [crate::Driver] around a blocking I2C peripheral,
+//! such that the register layout and bounds checks are shared with the async driver.
+//! As the blocking I2C peripheral completes every transaction immediately,
+//! each call runs to completion without an executor.
+
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+use crate::{marker, Address, Config, Error, LP50xx, Rgb};
+
+/// Async I2C interface around a blocking I2C peripheral.
+///
+/// All operations complete immediately, blocking on the underlying peripheral.
+pub struct BlockingInterface<T> {
+    i2c: T,
+}
+
+impl<T: I2c> BlockingInterface<T> {
+    /// Wrap a blocking I2C peripheral.
+    pub const fn new(i2c: T) -> Self {
+        Self { i2c }
+    }
+
+    /// Consume the interface, returning the underlying I2C peripheral.
+    pub fn into_inner(self) -> T {
+        self.i2c
+    }
+}
+
+impl<T: I2c> ErrorType for BlockingInterface<T> {
+    type Error = T::Error;
+}
+
+impl<T: I2c> embedded_hal_async::i2c::I2c for BlockingInterface<T> {
+    async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.read(address, read)
+    }
+
+    async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        self.i2c.write(address, write)
+    }
+
+    async fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.i2c.write_read(address, write, read)
+    }
+
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.i2c.transaction(address, operations)
+    }
+}
+
+/// Run a future to completion by polling it in a loop.
+///
+/// Futures of a driver around a [BlockingInterface] complete on the first poll.
+/// Use this to call methods of [Driver::inner_mut] that are not mirrored by the blocking driver.
+/// Futures that await anything else, like an async delay, are busy-polled.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Blocking high level driver for the LP50xx family of devices.
+///
+/// Mirrors the commonly used methods of [crate::Driver],
+/// the remaining methods are reachable through [Driver::inner_mut] and [block_on].
+pub struct Driver<VARIANT: LP50xx, T: I2c, STATE: marker::Marker> {
+    inner: crate::Driver<VARIANT, BlockingInterface<T>, STATE>,
+}
+
+impl<VARIANT: LP50xx, T: I2c> Driver<VARIANT, T, marker::Standby> {
+    /// Construct the blocking driver for a specific IC variant.
+    pub fn new(interface: T, address: Address) -> Self {
+        Self {
+            inner: VARIANT::new(BlockingInterface::new(interface), address),
+        }
+    }
+
+    /// Enable the device, allowing the LEDs to be turned on.
+    pub fn enable(self) -> Result<Driver<VARIANT, T, marker::Normal>, Error<T::Error>> {
+        Ok(Driver {
+            inner: block_on(self.inner.enable())?,
+        })
+    }
+}
+
+impl<VARIANT: LP50xx, T: I2c> Driver<VARIANT, T, marker::Normal> {
+    /// Disable the device, turning all LEDs off.
+    pub fn disable(self) -> Result<Driver<VARIANT, T, marker::Standby>, Error<T::Error>> {
+        Ok(Driver {
+            inner: block_on(self.inner.disable())?,
+        })
+    }
+}
+
+impl<VARIANT: LP50xx, T: I2c, STATE: marker::Marker> Driver<VARIANT, T, STATE> {
+    /// The wrapped async driver, for methods not mirrored by the blocking driver.
+    pub fn inner_mut(&mut self) -> &mut crate::Driver<VARIANT, BlockingInterface<T>, STATE> {
+        &mut self.inner
+    }
+
+    /// Consume the driver, returning the underlying I2C peripheral.
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner().into_inner()
+    }
+
+    /// Reset all registers to their defaults, see [crate::Driver::reset].
+    pub fn reset(self) -> Result<Driver<VARIANT, T, marker::Standby>, Error<T::Error>> {
+        Ok(Driver {
+            inner: block_on(self.inner.reset())?,
+        })
+    }
+
+    /// Apply a configuration, see [crate::Driver::configure].
+    pub fn configure(&mut self, config: &Config) -> Result<(), Error<T::Error>> {
+        block_on(self.inner.configure(config))
+    }
+
+    /// Read back the configuration, see [crate::Driver::read_config].
+    pub fn read_config(&mut self) -> Result<Config, Error<T::Error>> {
+        block_on(self.inner.read_config())
+    }
+}
+
+impl<VARIANT: LP50xx, T: I2c, STATE: marker::Individual> Driver<VARIANT, T, STATE> {
+    /// Set the value of a single OUT channel, see [crate::Driver::set_channel].
+    pub fn set_channel(&mut self, channel_i: u8, value: u8) -> Result<(), Error<T::Error>> {
+        block_on(self.inner.set_channel(channel_i, value))
+    }
+
+    /// Read back the value of a single OUT channel, see [crate::Driver::read_channel].
+    pub fn read_channel(&mut self, channel_i: u8) -> Result<u8, Error<T::Error>> {
+        block_on(self.inner.read_channel(channel_i))
+    }
+
+    /// Set the RGB LED color values, see [crate::Driver::set_rgb].
+    pub fn set_rgb(&mut self, rgb_i: u8, value: impl Into<Rgb>) -> Result<(), Error<T::Error>> {
+        block_on(self.inner.set_rgb(rgb_i, value))
+    }
+
+    /// Set the colors of all RGB LEDs, see [crate::Driver::set_all_rgb].
+    pub fn set_all_rgb(&mut self, colors: &[Rgb]) -> Result<(), Error<T::Error>> {
+        block_on(self.inner.set_all_rgb(colors))
+    }
+
+    /// Read back the RGB LED color values, see [crate::Driver::read_rgb].
+    pub fn read_rgb(&mut self, rgb_i: u8) -> Result<Rgb, Error<T::Error>> {
+        block_on(self.inner.read_rgb(rgb_i))
+    }
+
+    /// Set the brightness of a RGB LED, see [crate::Driver::set_rgb_brightness].
+    pub fn set_rgb_brightness(&mut self, rgb_i: u8, value: u8) -> Result<(), Error<T::Error>> {
+        block_on(self.inner.set_rgb_brightness(rgb_i, value))
+    }
+
+    /// Set the brightness of all RGB LEDs, see [crate::Driver::set_all_brightness].
+    pub fn set_all_brightness(&mut self, value: u8) -> Result<(), Error<T::Error>> {
+        block_on(self.inner.set_all_brightness(value))
+    }
+
+    /// Turn all OUT channels off, see [crate::Driver::off].
+    pub fn off(&mut self) -> Result<(), Error<T::Error>> {
+        block_on(self.inner.off())
+    }
+}
@@ -0,0 +1,559 @@
+use core::{ops::Range, task::Poll};
+
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+use crate::{
+    hl::{color::hsv_to_rgb, MAX_LED_COUNT, MAX_RGB_COUNT},
+    marker, Driver, Error, LP50xx, Rgb,
+};
+
+/// Fade of a single RGB LED towards a target color, advanced one step at a time.
+///
+/// Intended for cooperative schedulers without async timers:
+/// call [FadeJob::poll] once every scheduler tick until it returns [Poll::Ready].
+#[derive(Debug, Clone)]
+pub struct FadeJob {
+    rgb_i: u8,
+    from: Rgb,
+    to: Rgb,
+    steps: u16,
+    step: u16,
+}
+
+impl FadeJob {
+    /// Fade RGB LED `rgb_i` from `from` to `to` over `steps` steps.
+    ///
+    /// A `steps` of 0 is treated as 1, immediately setting the target color on the first poll.
+    pub fn new(rgb_i: u8, from: impl Into<Rgb>, to: impl Into<Rgb>, steps: u16) -> Self {
+        Self {
+            rgb_i,
+            from: from.into(),
+            to: to.into(),
+            steps: steps.max(1),
+            step: 0,
+        }
+    }
+
+    /// Whether the target color has been written.
+    pub fn is_done(&self) -> bool {
+        self.step == self.steps
+    }
+
+    /// Advance the fade by one step, writing the intermediate color.
+    ///
+    /// Returns [Poll::Ready] once the target color has been written, after which no more writes are issued.
+    pub async fn poll<VARIANT: LP50xx, T: I2c, STATE: marker::Individual, const N: usize>(
+        &mut self,
+        driver: &mut Driver<VARIANT, T, STATE, N>,
+    ) -> Result<Poll<()>, Error<T::Error>> {
+        if self.is_done() {
+            return Ok(Poll::Ready(()));
+        }
+
+        self.step += 1;
+        let t = (self.step as u32 * 255 / self.steps as u32) as u8;
+        driver
+            .set_rgb(self.rgb_i, self.from.lerp(self.to, t))
+            .await?;
+
+        Ok(if self.is_done() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        })
+    }
+}
+
+/// Up to `N` [FadeJob]s advanced together, reporting which RGB LEDs reached their target.
+///
+/// Enables chained animations: when the fade of one RGB LED completes, start the next one.
+#[derive(Debug, Clone, Default)]
+pub struct FadeSet<const N: usize> {
+    jobs: heapless::Vec<FadeJob, N>,
+}
+
+impl<const N: usize> FadeSet<N> {
+    /// Construct a set without any fades.
+    pub const fn new() -> Self {
+        Self {
+            jobs: heapless::Vec::new(),
+        }
+    }
+
+    /// Add a fade, returning it back if the set is full.
+    pub fn add(&mut self, job: FadeJob) -> Result<(), FadeJob> {
+        self.jobs.push(job)
+    }
+
+    /// Whether all fades have completed.
+    pub fn is_done(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Advance all fades by one step.
+    ///
+    /// Returns a bitmask with bit `n` set if RGB LED `n` reached its target color during this tick.
+    /// Completed fades are removed from the set, making room for new ones.
+    pub async fn tick<VARIANT: LP50xx, T: I2c, STATE: marker::Individual, const BUFFER: usize>(
+        &mut self,
+        driver: &mut Driver<VARIANT, T, STATE, BUFFER>,
+    ) -> Result<u16, Error<T::Error>> {
+        let mut completed = 0;
+        for job in self.jobs.iter_mut() {
+            if job.poll(driver).await?.is_ready() {
+                completed |= 1 << job.rgb_i;
+            }
+        }
+
+        self.jobs.retain(|job| !job.is_done());
+        Ok(completed)
+    }
+}
+
+/// Easing curve, shaping the progress of a transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Easing {
+    /// Constant speed.
+    #[default]
+    Linear,
+    /// Start slow, end fast (quadratic).
+    EaseIn,
+    /// Start fast, end slow (quadratic).
+    EaseOut,
+    /// Start and end slow, fast in the middle (quadratic).
+    EaseInOut,
+}
+
+impl Easing {
+    /// Map the linear progress `t` to the eased progress, where `0` is the start and `255` the end.
+    ///
+    /// The start and end are always mapped onto themselves.
+    pub const fn apply(self, t: u8) -> u8 {
+        let t = t as u32;
+        let inv = 255 - t;
+        (match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t / 255,
+            Easing::EaseOut => 255 - inv * inv / 255,
+            Easing::EaseInOut if t < 128 => 2 * t * t / 255,
+            Easing::EaseInOut => 255 - 2 * inv * inv / 255,
+        }) as u8
+    }
+}
+
+/// Timing of [Driver::blink_binary], in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct BlinkTiming {
+    /// Duration of the start and end marker pulses.
+    pub marker_ms: u32,
+    /// Duration each bit is shown.
+    pub bit_ms: u32,
+    /// Duration the LED is dark after each pulse.
+    pub gap_ms: u32,
+}
+
+impl Default for BlinkTiming {
+    fn default() -> Self {
+        Self {
+            marker_ms: 1000,
+            bit_ms: 300,
+            gap_ms: 300,
+        }
+    }
+}
+
+impl<VARIANT: LP50xx, T: I2c, STATE: marker::Individual, const N: usize>
+    Driver<VARIANT, T, STATE, N>
+{
+    /// Play a sequence of pre-encoded frames, waiting `step_us` microseconds after each frame.
+    ///
+    /// Each frame contains the values of all OUT channels, and is thus `LED_COUNT` bytes long.
+    /// Useful for animations baked by host tooling and stored in flash.
+    ///
+    /// Will return the [Error::Index] on the first frame with a different length,
+    /// after having played all frames before it.
+    pub async fn play_stream<'a>(
+        &mut self,
+        frames: impl IntoIterator<Item = &'a [u8]>,
+        delay: &mut impl DelayNs,
+        step_us: u32,
+    ) -> Result<(), Error<T::Error>> {
+        for frame in frames {
+            if frame.len() != VARIANT::LED_COUNT as usize {
+                return Err(Error::Index);
+            }
+
+            let mut buf = [0u8; MAX_LED_COUNT];
+            let buf = &mut buf[..frame.len()];
+            buf.copy_from_slice(frame);
+            self.correct_channels(0, buf);
+
+            self.write_block(VARIANT::OUT_START_ADDRESS, buf).await?;
+            delay.delay_us(step_us).await;
+        }
+        Ok(())
+    }
+
+    /// Blink out the bits of `value` on a single RGB LED, most significant bit first.
+    ///
+    /// Starts and ends with a marker pulse in the `on` color, lasting [BlinkTiming::marker_ms].
+    /// In between, each bit is shown in the `on` (1) or `off` (0) color for [BlinkTiming::bit_ms].
+    /// After each pulse the RGB LED is dark for [BlinkTiming::gap_ms], and it is left dark when done.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED.
+    pub async fn blink_binary(
+        &mut self,
+        rgb_i: u8,
+        value: u8,
+        on: impl Into<Rgb>,
+        off: impl Into<Rgb>,
+        timing: BlinkTiming,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        let (on, off) = (on.into(), off.into());
+
+        let bits = (0..8).rev().map(|i| {
+            if value & (1 << i) != 0 {
+                (on, timing.bit_ms)
+            } else {
+                (off, timing.bit_ms)
+            }
+        });
+        let marker = core::iter::once((on, timing.marker_ms));
+
+        for (color, duration_ms) in marker.clone().chain(bits).chain(marker) {
+            self.set_rgb(rgb_i, color).await?;
+            delay.delay_ms(duration_ms).await;
+            self.set_rgb(rgb_i, Rgb([0; 3])).await?;
+            delay.delay_ms(timing.gap_ms).await;
+        }
+        Ok(())
+    }
+
+    /// Fade a single RGB LED from `start` to `end` in `steps` writes, waiting `step_us` microseconds after each write.
+    ///
+    /// The first write is `start` and the last write is `end`,
+    /// with `easing` applied to the interpolation of each color channel in between.
+    ///
+    /// A `steps` of 0 is treated as 1, immediately setting `end`.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fade_channels(
+        &mut self,
+        rgb_i: u8,
+        start: impl Into<Rgb>,
+        end: impl Into<Rgb>,
+        steps: u16,
+        easing: Easing,
+        delay: &mut impl DelayNs,
+        step_us: u32,
+    ) -> Result<(), Error<T::Error>> {
+        if rgb_i >= VARIANT::RGB_COUNT {
+            return Err(Error::Index);
+        }
+
+        let (start, end) = (start.into(), end.into());
+        let last = steps.max(1) as u32 - 1;
+
+        for step in 0..=last {
+            let t = match last {
+                0 => 255,
+                _ => (step * 255 / last) as u8,
+            };
+
+            self.set_rgb(rgb_i, start.lerp(end, easing.apply(t)))
+                .await?;
+            delay.delay_us(step_us).await;
+        }
+        Ok(())
+    }
+
+    /// Breathe a single RGB LED in `color` once, ramping its brightness up and back down over `period_ms` milliseconds.
+    ///
+    /// Sets the color first, then only writes the brightness register: 33 writes from 0 up to 255 and back to 0,
+    /// with 32 delays of `period_ms / 32` in between. The RGB LED is left at a brightness of 0 when done.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED.
+    pub async fn breathe(
+        &mut self,
+        rgb_i: u8,
+        color: impl Into<Rgb>,
+        period_ms: u32,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        const STEPS: u32 = 16;
+
+        if rgb_i >= VARIANT::RGB_COUNT {
+            return Err(Error::Index);
+        }
+
+        self.set_rgb(rgb_i, color).await?;
+
+        let step_us = period_ms.saturating_mul(1000) / (2 * STEPS);
+        for step in 0..=2 * STEPS {
+            let level = step.min(2 * STEPS - step);
+            let brightness = (level * 255 / STEPS) as u8;
+
+            self.set_rgb_brightness(rgb_i, brightness).await?;
+            if step < 2 * STEPS {
+                delay.delay_us(step_us).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Crossfade all RGB LEDs from the frame `from` to the frame `to` in `steps` frames,
+    /// waiting `step_delay_ms` milliseconds after each frame.
+    ///
+    /// Each RGB LED is interpolated with [Rgb::lerp]. The `from` frame itself is not written,
+    /// the last frame is `to`. A `steps` of 0 is treated as 1, immediately setting `to`.
+    ///
+    /// Will return the [Error::Index] without writing anything if either frame is not exactly `RGB_COUNT` long.
+    pub async fn crossfade(
+        &mut self,
+        from: &[Rgb],
+        to: &[Rgb],
+        steps: u16,
+        step_delay_ms: u32,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        let count = VARIANT::RGB_COUNT as usize;
+        if from.len() != count || to.len() != count {
+            return Err(Error::Index);
+        }
+
+        let steps = steps.max(1) as u32;
+        let mut frame = [Rgb([0; 3]); MAX_RGB_COUNT];
+        for step in 1..=steps {
+            let t = ((step * 255 + steps / 2) / steps) as u8;
+            for ((color, from), to) in frame.iter_mut().zip(from).zip(to) {
+                *color = from.lerp(*to, t);
+            }
+
+            self.write_rgbs(0, &frame[..count]).await?;
+            delay.delay_ms(step_delay_ms).await;
+        }
+        Ok(())
+    }
+
+    /// Pulse a single RGB LED `pulses` times in `color` as a notification, leaving it off afterwards.
+    ///
+    /// Each pulse lasts 100ms, followed by 100ms off.
+    /// Only the indexed RGB LED is written, the rest of the frame is left untouched.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED.
+    pub async fn notify(
+        &mut self,
+        rgb_i: u8,
+        color: impl Into<Rgb>,
+        pulses: u8,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        const PULSE_MS: u32 = 100;
+
+        if rgb_i >= VARIANT::RGB_COUNT {
+            return Err(Error::Index);
+        }
+
+        let color = color.into();
+        for _ in 0..pulses {
+            self.set_rgb(rgb_i, color).await?;
+            delay.delay_ms(PULSE_MS).await;
+            self.set_rgb(rgb_i, Rgb([0; 3])).await?;
+            delay.delay_ms(PULSE_MS).await;
+        }
+        Ok(())
+    }
+
+    /// Sweep all RGB LEDs through the hue spectrum together, as a single color.
+    ///
+    /// The hue starts at 0 degrees and advances `step_deg` degrees each frame,
+    /// waiting `step_delay_us` microseconds after each frame.
+    /// Saturation and value remain fixed at `saturation_value`.
+    /// Returns after `cycles` full sweeps through the spectrum.
+    ///
+    /// A `step_deg` of 0 is treated as 1.
+    pub async fn run_hue_sweep(
+        &mut self,
+        saturation_value: (u8, u8),
+        step_deg: u16,
+        step_delay_us: u32,
+        cycles: u16,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        let (saturation, value) = saturation_value;
+        let step_deg = step_deg.max(1) as u32;
+        let frames = cycles as u32 * 360u32.div_ceil(step_deg);
+
+        let mut hue_deg = 0;
+        for _ in 0..frames {
+            let color = hsv_to_rgb((hue_deg * 256 / 360) as u8, saturation, value);
+            let frame = [color; MAX_RGB_COUNT];
+            self.write_rgbs(0, &frame[..VARIANT::RGB_COUNT as usize])
+                .await?;
+            delay.delay_us(step_delay_us).await;
+
+            hue_deg = (hue_deg + step_deg) % 360;
+        }
+        Ok(())
+    }
+
+    /// Show a rainbow spread evenly across all RGB LEDs, at full saturation and value.
+    ///
+    /// The hue of each RGB LED is offset by `phase`, call this in a loop incrementing `phase` to cycle the colors.
+    /// Advancing `phase` by `256 / RGB_COUNT` moves the pattern by one RGB LED towards higher indices.
+    pub async fn rainbow_step(&mut self, phase: u8) -> Result<(), Error<T::Error>> {
+        let count = VARIANT::RGB_COUNT as u16;
+        let mut frame = [Rgb([0; 3]); MAX_RGB_COUNT];
+        for (i, color) in frame[..count as usize].iter_mut().enumerate() {
+            let offset = (i as u16 * 256 / count) as u8;
+            *color = hsv_to_rgb(phase.wrapping_sub(offset), 255, 255);
+        }
+        self.set_all_rgb(&frame[..count as usize]).await
+    }
+}
+
+/// An effect rendering the colors for a range of RGB LEDs, frame by frame.
+pub trait Animation {
+    /// Render the next frame into `frame`, with one color per RGB LED in the assigned range.
+    fn render(&mut self, frame: &mut [Rgb]);
+}
+
+/// Dashes of a highlight color scrolling over a base color, indicating a selected section of an LED bar.
+///
+/// Dashes and gaps are both `dash` RGB LEDs long, and the pattern moves one RGB LED further
+/// every `frames_per_shift` frames. The range is assigned when adding the effect to a [Scheduler].
+#[derive(Debug, Clone)]
+pub struct MarchingAnts {
+    highlight: Rgb,
+    base: Rgb,
+    dash: u8,
+    frames_per_shift: u16,
+    frame: u16,
+    offset: u16,
+}
+
+impl MarchingAnts {
+    /// Construct the effect, starting with a dash at the start of the range.
+    ///
+    /// A `dash` or `frames_per_shift` of 0 is treated as 1.
+    pub fn new(
+        highlight: impl Into<Rgb>,
+        base: impl Into<Rgb>,
+        dash: u8,
+        frames_per_shift: u16,
+    ) -> Self {
+        Self {
+            highlight: highlight.into(),
+            base: base.into(),
+            dash: dash.max(1),
+            frames_per_shift: frames_per_shift.max(1),
+            frame: 0,
+            offset: 0,
+        }
+    }
+
+    /// Color of the RGB LED at index `i` of the range in the current frame.
+    pub fn color_at(&self, i: u8) -> Rgb {
+        let period = 2 * self.dash as u16;
+        let position = (i as u16 + period - self.offset) % period;
+        if position < self.dash as u16 {
+            self.highlight
+        } else {
+            self.base
+        }
+    }
+
+    /// Move the pattern one RGB LED further.
+    pub fn shift(&mut self) {
+        self.offset = (self.offset + 1) % (2 * self.dash as u16);
+    }
+}
+
+impl Animation for MarchingAnts {
+    fn render(&mut self, frame: &mut [Rgb]) {
+        for (i, value) in frame.iter_mut().enumerate() {
+            *value = self.color_at(i as u8);
+        }
+
+        self.frame += 1;
+        if self.frame == self.frames_per_shift {
+            self.frame = 0;
+            self.shift();
+        }
+    }
+}
+
+/// Reason an [Animation] could not be added to a [Scheduler].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ScheduleError {
+    /// The range overlaps with the range of an already scheduled animation.
+    Overlap,
+    /// The scheduler can not hold any more animations.
+    Full,
+}
+
+/// Runs up to `N` animations simultaneously, each on a disjoint range of RGB LEDs.
+///
+/// RGB LEDs not covered by any animation are turned off.
+pub struct Scheduler<'a, const N: usize> {
+    animations: heapless::Vec<(Range<u8>, &'a mut dyn Animation), N>,
+}
+
+impl<'a, const N: usize> Default for Scheduler<'a, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, const N: usize> Scheduler<'a, N> {
+    /// Construct a scheduler without any animations.
+    pub const fn new() -> Self {
+        Self {
+            animations: heapless::Vec::new(),
+        }
+    }
+
+    /// Run `animation` on the RGB LEDs in `range`.
+    pub fn add(
+        &mut self,
+        range: Range<u8>,
+        animation: &'a mut dyn Animation,
+    ) -> Result<(), ScheduleError> {
+        if self
+            .animations
+            .iter()
+            .any(|(other, _)| range.start < other.end && other.start < range.end)
+        {
+            return Err(ScheduleError::Overlap);
+        }
+
+        self.animations
+            .push((range, animation))
+            .map_err(|_| ScheduleError::Full)
+    }
+
+    /// Render the next frame of all animations, and write the combined frame to the device.
+    ///
+    /// Will return the [Error::Index] if any range exceeds the RGB LEDs of the device.
+    pub async fn step<VARIANT: LP50xx, T: I2c, STATE: marker::Individual, const BUFFER: usize>(
+        &mut self,
+        driver: &mut Driver<VARIANT, T, STATE, BUFFER>,
+    ) -> Result<(), Error<T::Error>> {
+        let mut frame = [Rgb([0; 3]); MAX_RGB_COUNT];
+        let frame = &mut frame[..VARIANT::RGB_COUNT as usize];
+
+        for (range, animation) in self.animations.iter_mut() {
+            let range = frame
+                .get_mut(range.start as usize..range.end as usize)
+                .ok_or(Error::Index)?;
+            animation.render(range);
+        }
+
+        driver.write_rgbs(0, frame).await
+    }
+}
@@ -0,0 +1,287 @@
+use crate::Rgb;
+
+/// Duty cycle produced by each brightness code when logarithmic scaling is enabled.
+///
+/// Approximates the logarithmic dimming curve from the datasheet as `duty = 2^(8 * code / 255) - 1`.
+const LOG_SCALE: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3,
+    3, 3, 3, 3, 3, 3, 4, 4, 4, 4, 4, 4, 4, 4, 4, 5, 5, 5, 5, 5, 5, 5, 5, 6, 6, 6, 6, 6, 6, 7, 7, 7,
+    7, 7, 7, 8, 8, 8, 8, 8, 9, 9, 9, 9, 9, 10, 10, 10, 10, 11, 11, 11, 11, 12, 12, 12, 13, 13, 13,
+    14, 14, 14, 14, 15, 15, 16, 16, 16, 17, 17, 17, 18, 18, 19, 19, 20, 20, 20, 21, 21, 22, 22, 23,
+    23, 24, 25, 25, 26, 26, 27, 27, 28, 29, 29, 30, 31, 31, 32, 33, 34, 34, 35, 36, 37, 38, 38, 39,
+    40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 54, 55, 56, 57, 59, 60, 61, 63, 64, 65, 67,
+    68, 70, 72, 73, 75, 76, 78, 80, 82, 83, 85, 87, 89, 91, 93, 95, 97, 99, 102, 104, 106, 109,
+    111, 114, 116, 119, 121, 124, 127, 129, 132, 135, 138, 141, 144, 148, 151, 154, 158, 161, 165,
+    168, 172, 176, 180, 184, 188, 192, 196, 201, 205, 209, 214, 219, 224, 229, 234, 239, 244, 249,
+    255,
+];
+
+/// Gamma correction table for a gamma of 2.2, mapping a perceived brightness to a linear duty cycle.
+///
+/// Computed as `round(255 * (i / 255)^2.2)`.
+pub const GAMMA: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2,
+    3, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 8, 8, 8, 9, 9, 9, 10, 10, 11, 11,
+    11, 12, 12, 13, 13, 13, 14, 14, 15, 15, 16, 16, 17, 17, 18, 18, 19, 19, 20, 20, 21, 22, 22, 23,
+    23, 24, 25, 25, 26, 26, 27, 28, 28, 29, 30, 30, 31, 32, 33, 33, 34, 35, 35, 36, 37, 38, 39, 39,
+    40, 41, 42, 43, 43, 44, 45, 46, 47, 48, 49, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61,
+    62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 73, 74, 75, 76, 77, 78, 79, 81, 82, 83, 84, 85, 87, 88,
+    89, 90, 91, 93, 94, 95, 97, 98, 99, 100, 102, 103, 105, 106, 107, 109, 110, 111, 113, 114, 116,
+    117, 119, 120, 121, 123, 124, 126, 127, 129, 130, 132, 133, 135, 137, 138, 140, 141, 143, 145,
+    146, 148, 149, 151, 153, 154, 156, 158, 159, 161, 163, 165, 166, 168, 170, 172, 173, 175, 177,
+    179, 181, 182, 184, 186, 188, 190, 192, 194, 196, 197, 199, 201, 203, 205, 207, 209, 211, 213,
+    215, 217, 219, 221, 223, 225, 227, 229, 231, 234, 236, 238, 240, 242, 244, 246, 248, 251, 253,
+    255,
+];
+
+/// Convert a linear brightness into the value to write when logarithmic scaling is enabled.
+///
+/// Applications computing perceived brightness linearly can use this to get similar output
+/// regardless of the `log_scale` setting in [crate::Config].
+/// As the logarithmic curve is very flat near zero, small values map to the same code.
+pub fn linear_to_log(value: u8) -> u8 {
+    LOG_SCALE.partition_point(|&duty| duty < value) as u8
+}
+
+/// Convert a value written with logarithmic scaling enabled into the equivalent linear brightness.
+///
+/// Inverse of [linear_to_log].
+pub fn log_to_linear(value: u8) -> u8 {
+    LOG_SCALE[value as usize]
+}
+
+/// Map a reading onto a two-color gradient, from `cold` at `0` to `hot` at `255`.
+///
+/// Useful to visualize a sensor value on a status LED, for example blue to red for a temperature.
+pub fn color_scale(value: u8, cold: impl Into<Rgb>, hot: impl Into<Rgb>) -> Rgb {
+    cold.into().lerp(hot.into(), value)
+}
+
+/// Multiply two channel values as fractions of 255, rounding to the nearest value.
+const fn mul_channel(a: u8, b: u8) -> u8 {
+    ((a as u16 * b as u16 + 127) / 255) as u8
+}
+
+/// Color in HSV representation, with the hue spanning the full `u8` range.
+///
+/// The hue wraps around at 256: `0` is red, `85` is green and `171` is blue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hsv {
+    pub h: u8,
+    pub s: u8,
+    pub v: u8,
+}
+
+impl From<Hsv> for Rgb {
+    fn from(value: Hsv) -> Self {
+        hsv_to_rgb(value.h, value.s, value.v)
+    }
+}
+
+/// Convert a color in HSV representation to RGB, with the hue spanning the full `u8` range.
+pub(crate) const fn hsv_to_rgb(h: u8, s: u8, v: u8) -> Rgb {
+    let h6 = h as u16 * 6;
+    let (sextant, fraction) = (h6 / 256, (h6 % 256) as u8);
+
+    let p = mul_channel(v, 255 - s);
+    let q = mul_channel(v, 255 - mul_channel(s, fraction));
+    let t = mul_channel(v, 255 - mul_channel(s, 255 - fraction));
+
+    Rgb(match sextant {
+        0 => [v, t, p],
+        1 => [q, v, p],
+        2 => [p, v, t],
+        3 => [p, q, v],
+        4 => [t, p, v],
+        _ => [v, p, q],
+    })
+}
+
+/// Convert an RGB color to its HSV representation, with the hue spanning the full `u8` range.
+pub(crate) fn rgb_to_hsv(rgb: Rgb) -> (u8, u8, u8) {
+    let [r, g, b] = rgb.0.map(|c| c as i32);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+
+    if delta == 0 {
+        return (0, 0, max as u8);
+    }
+
+    let s = (255 * delta + max / 2) / max;
+
+    // Each sextant of the hue spans 256 / 6 steps.
+    let (offset, difference) = if max == r {
+        (0, g - b)
+    } else if max == g {
+        (512, b - r)
+    } else {
+        (1024, r - g)
+    };
+    let h = (offset + difference * 256 / delta).div_euclid(6);
+
+    (h.rem_euclid(256) as u8, s as u8, max as u8)
+}
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb([0x00, 0x00, 0x00]);
+    pub const WHITE: Rgb = Rgb([0xFF, 0xFF, 0xFF]);
+    pub const RED: Rgb = Rgb([0xFF, 0x00, 0x00]);
+    pub const GREEN: Rgb = Rgb([0x00, 0xFF, 0x00]);
+    pub const BLUE: Rgb = Rgb([0x00, 0x00, 0xFF]);
+    pub const YELLOW: Rgb = Rgb([0xFF, 0xFF, 0x00]);
+    pub const CYAN: Rgb = Rgb([0x00, 0xFF, 0xFF]);
+    pub const MAGENTA: Rgb = Rgb([0xFF, 0x00, 0xFF]);
+
+    /// Gray color with all channels set to `value`.
+    pub const fn gray(value: u8) -> Rgb {
+        Rgb([value; 3])
+    }
+
+    /// Color of a hex color code in the form `0xRRGGBB`, the most significant byte is ignored.
+    pub const fn from_hex(hex: u32) -> Rgb {
+        let [_, r, g, b] = hex.to_be_bytes();
+        Rgb([r, g, b])
+    }
+
+    /// Color of a code of the 256-color terminal palette, as used by xterm.
+    ///
+    /// Codes 0 to 15 are the system colors, 16 to 231 the 6x6x6 color cube and 232 to 255 the grayscale ramp.
+    pub const fn from_ansi256(code: u8) -> Rgb {
+        const SYSTEM: [[u8; 3]; 16] = [
+            [0x00, 0x00, 0x00],
+            [0x80, 0x00, 0x00],
+            [0x00, 0x80, 0x00],
+            [0x80, 0x80, 0x00],
+            [0x00, 0x00, 0x80],
+            [0x80, 0x00, 0x80],
+            [0x00, 0x80, 0x80],
+            [0xC0, 0xC0, 0xC0],
+            [0x80, 0x80, 0x80],
+            [0xFF, 0x00, 0x00],
+            [0x00, 0xFF, 0x00],
+            [0xFF, 0xFF, 0x00],
+            [0x00, 0x00, 0xFF],
+            [0xFF, 0x00, 0xFF],
+            [0x00, 0xFF, 0xFF],
+            [0xFF, 0xFF, 0xFF],
+        ];
+        const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+        match code {
+            0..16 => Rgb(SYSTEM[code as usize]),
+            16..232 => {
+                let i = (code - 16) as usize;
+                Rgb([
+                    CUBE_LEVELS[i / 36],
+                    CUBE_LEVELS[i / 6 % 6],
+                    CUBE_LEVELS[i % 6],
+                ])
+            }
+            232.. => Rgb::gray(8 + 10 * (code - 232)),
+        }
+    }
+
+    /// Approximate color of a black body at a color temperature of `kelvin`, for tunable white effects.
+    ///
+    /// Interpolates between points on the curve every 500K, temperatures are clamped to the range of 1000K to 12000K.
+    pub fn from_kelvin(kelvin: u16) -> Rgb {
+        const MIN: u16 = 1000;
+        const MAX: u16 = 12000;
+        const STEP: u16 = 500;
+        const CURVE: [[u8; 3]; 23] = [
+            [255, 68, 0],
+            [255, 108, 0],
+            [255, 137, 14],
+            [255, 159, 70],
+            [255, 177, 110],
+            [255, 193, 141],
+            [255, 206, 166],
+            [255, 218, 187],
+            [255, 228, 206],
+            [255, 237, 222],
+            [255, 246, 237],
+            [255, 254, 250],
+            [243, 242, 255],
+            [230, 235, 255],
+            [221, 230, 255],
+            [215, 226, 255],
+            [210, 223, 255],
+            [205, 220, 255],
+            [202, 218, 255],
+            [199, 216, 255],
+            [196, 214, 255],
+            [193, 213, 255],
+            [191, 211, 255],
+        ];
+
+        let offset = kelvin.clamp(MIN, MAX) - MIN;
+        let (i, remainder) = ((offset / STEP) as usize, offset % STEP);
+        if remainder == 0 {
+            return Rgb(CURVE[i]);
+        }
+
+        let t = (remainder as u32 * 255 / STEP as u32) as u8;
+        Rgb(CURVE[i]).lerp(Rgb(CURVE[i + 1]), t)
+    }
+
+    /// Linearly interpolate each channel between `self` (at `t == 0`) and `other` (at `t == 255`).
+    ///
+    /// Rounds to the nearest value.
+    pub fn lerp(self, other: Rgb, t: u8) -> Rgb {
+        let lerp =
+            |a: u8, b: u8| ((a as u16 * (255 - t as u16) + b as u16 * t as u16 + 127) / 255) as u8;
+        Rgb([
+            lerp(self[0], other[0]),
+            lerp(self[1], other[1]),
+            lerp(self[2], other[2]),
+        ])
+    }
+
+    /// Scale each channel by `factor / 255`, rounding to the nearest value.
+    ///
+    /// Allows dimming in software, independent of the brightness registers of the device.
+    pub const fn scale(self, factor: u8) -> Rgb {
+        let c = self.0;
+        Rgb([
+            mul_channel(c[0], factor),
+            mul_channel(c[1], factor),
+            mul_channel(c[2], factor),
+        ])
+    }
+
+    /// Multiply blend mode, darkening `self` by `other`.
+    ///
+    /// Each channel is multiplied as a fraction of 255: white leaves a color unchanged, black results in black.
+    pub const fn multiply(self, other: Rgb) -> Rgb {
+        let (a, b) = (self.0, other.0);
+        Rgb([
+            mul_channel(a[0], b[0]),
+            mul_channel(a[1], b[1]),
+            mul_channel(a[2], b[2]),
+        ])
+    }
+
+    /// Screen blend mode, brightening `self` by `other`.
+    ///
+    /// The inverse of multiplying the inverted colors: black leaves a color unchanged, white results in white.
+    pub const fn screen(self, other: Rgb) -> Rgb {
+        let (a, b) = (self.0, other.0);
+        Rgb([
+            255 - mul_channel(255 - a[0], 255 - b[0]),
+            255 - mul_channel(255 - a[1], 255 - b[1]),
+            255 - mul_channel(255 - a[2], 255 - b[2]),
+        ])
+    }
+
+    /// Set the HSV value of the color, preserving hue and saturation.
+    ///
+    /// Sets the perceived brightness more faithfully than scaling each channel,
+    /// which can shift the apparent hue at low levels due to rounding.
+    pub fn with_value(self, v: u8) -> Rgb {
+        let (h, s, _) = rgb_to_hsv(self);
+        hsv_to_rgb(h, s, v)
+    }
+}
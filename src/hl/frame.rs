@@ -0,0 +1,202 @@
+use core::{
+    convert::Infallible,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use embedded_hal_async::i2c::I2c;
+
+use crate::{hl::MAX_RGB_COUNT, marker, Driver, Error, LP50xx, Rgb};
+
+/// Colors of all RGB LEDs of a device, kept in RAM and written to the device with [FrameBuffer::flush]
+/// or [FrameBuffer::commit].
+///
+/// Dereferences to a slice of exactly `RGB_COUNT` colors.
+/// Changes through [FrameBuffer::set] are tracked per RGB LED,
+/// whereas mutably dereferencing marks all RGB LEDs as changed.
+#[derive(Debug, Clone)]
+pub struct FrameBuffer<VARIANT: LP50xx> {
+    colors: [Rgb; MAX_RGB_COUNT],
+    /// Bit `n` is set if RGB LED `n` changed since the last write to the device.
+    dirty: u16,
+    marker: PhantomData<VARIANT>,
+}
+
+impl<VARIANT: LP50xx> Default for FrameBuffer<VARIANT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<VARIANT: LP50xx> FrameBuffer<VARIANT> {
+    /// Construct a frame buffer with all RGB LEDs off.
+    pub const fn new() -> Self {
+        Self {
+            colors: [Rgb([0; 3]); MAX_RGB_COUNT],
+            dirty: u16::MAX,
+            marker: PhantomData,
+        }
+    }
+
+    /// Set the color of a single RGB LED, marking it as changed if it differs from the current color.
+    ///
+    /// Panics if the device does not have the indexed RGB LED, see [FrameBuffer::try_set] for a checked variant.
+    pub fn set(&mut self, rgb_i: u8, value: impl Into<Rgb>) {
+        if self.try_set(rgb_i, value).is_err() {
+            panic!("RGB LED {rgb_i} out of range");
+        }
+    }
+
+    /// Set the color of a single RGB LED, marking it as changed if it differs from the current color.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED.
+    pub fn try_set(&mut self, rgb_i: u8, value: impl Into<Rgb>) -> Result<(), Error<Infallible>> {
+        if rgb_i >= VARIANT::RGB_COUNT {
+            return Err(Error::Index);
+        }
+
+        let value = value.into();
+        let color = &mut self.colors[rgb_i as usize];
+        if *color != value {
+            *color = value;
+            self.dirty |= 1 << rgb_i;
+        }
+        Ok(())
+    }
+
+    /// Shift the colors `by` positions towards higher indices, or towards lower indices if negative.
+    ///
+    /// With `wrap` the colors shifted out on one end come back in on the other end,
+    /// otherwise the vacated positions are turned off.
+    /// Shifts larger than the number of RGB LEDs are allowed: without `wrap` this turns all RGB LEDs off.
+    pub fn scroll(&mut self, by: i8, wrap: bool) {
+        let colors = self.deref_mut();
+        let len = colors.len();
+        let distance = by.unsigned_abs() as usize;
+
+        if wrap {
+            let distance = distance % len;
+            if by >= 0 {
+                colors.rotate_right(distance);
+            } else {
+                colors.rotate_left(distance);
+            }
+        } else {
+            let distance = distance.min(len);
+            if by >= 0 {
+                colors.copy_within(..len - distance, distance);
+                colors[..distance].fill(Rgb([0; 3]));
+            } else {
+                colors.copy_within(distance.., 0);
+                colors[len - distance..].fill(Rgb([0; 3]));
+            }
+        }
+    }
+
+    /// Write all colors to the device.
+    pub async fn flush<T: I2c, STATE: marker::Individual, const N: usize>(
+        &mut self,
+        driver: &mut Driver<VARIANT, T, STATE, N>,
+    ) -> Result<(), Error<T::Error>> {
+        driver.write_rgbs(0, self).await?;
+        self.dirty = 0;
+        Ok(())
+    }
+
+    /// Write only the colors that changed since the last write to the device,
+    /// with a single block write per contiguous run of changed RGB LEDs.
+    ///
+    /// A new frame buffer is considered completely changed.
+    pub async fn commit<T: I2c, STATE: marker::Individual, const N: usize>(
+        &mut self,
+        driver: &mut Driver<VARIANT, T, STATE, N>,
+    ) -> Result<(), Error<T::Error>> {
+        let count = VARIANT::RGB_COUNT as usize;
+        let mut i = 0;
+        while i < count {
+            if self.dirty & (1 << i) == 0 {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            while i < count && self.dirty & (1 << i) != 0 {
+                i += 1;
+            }
+
+            driver
+                .write_rgbs(start as u8, &self.colors[start..i])
+                .await?;
+            // Clear the written run, so a retry after an error only writes the remainder.
+            self.dirty &= !(((1 << (i - start)) - 1) << start);
+        }
+
+        self.dirty = 0;
+        Ok(())
+    }
+}
+
+impl<VARIANT: LP50xx> Deref for FrameBuffer<VARIANT> {
+    type Target = [Rgb];
+
+    fn deref(&self) -> &Self::Target {
+        &self.colors[..VARIANT::RGB_COUNT as usize]
+    }
+}
+
+impl<VARIANT: LP50xx> DerefMut for FrameBuffer<VARIANT> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.dirty = u16::MAX;
+        &mut self.colors[..VARIANT::RGB_COUNT as usize]
+    }
+}
+
+/// Pair of frame buffers for tear-free updates: draw into the back buffer, then show it with [DoubleBuffer::present].
+///
+/// Dereferences to the back buffer.
+#[derive(Debug, Clone, Default)]
+pub struct DoubleBuffer<VARIANT: LP50xx> {
+    front: FrameBuffer<VARIANT>,
+    back: FrameBuffer<VARIANT>,
+}
+
+impl<VARIANT: LP50xx> DoubleBuffer<VARIANT> {
+    /// Construct a double buffer with all RGB LEDs off in both buffers.
+    pub const fn new() -> Self {
+        Self {
+            front: FrameBuffer::new(),
+            back: FrameBuffer::new(),
+        }
+    }
+
+    /// The colors last presented to the device.
+    pub fn front(&self) -> &[Rgb] {
+        &self.front
+    }
+
+    /// Swap the back buffer to the front and write the full frame to the device,
+    /// in as few transactions as the maximum I2C transfer size allows.
+    ///
+    /// Afterwards the back buffer holds the previously presented frame.
+    pub async fn present<T: I2c, STATE: marker::Individual, const N: usize>(
+        &mut self,
+        driver: &mut Driver<VARIANT, T, STATE, N>,
+    ) -> Result<(), Error<T::Error>> {
+        core::mem::swap(&mut self.front, &mut self.back);
+        self.front.flush(driver).await
+    }
+}
+
+impl<VARIANT: LP50xx> Deref for DoubleBuffer<VARIANT> {
+    type Target = FrameBuffer<VARIANT>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.back
+    }
+}
+
+impl<VARIANT: LP50xx> DerefMut for DoubleBuffer<VARIANT> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.back
+    }
+}
@@ -1,30 +1,75 @@
 #[cfg(test)]
 mod test;
 
+#[cfg(feature = "animations")]
+mod animation;
+mod color;
+mod frame;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "smart-leds")]
+mod smart_leds;
+
+#[cfg(feature = "animations")]
+pub use animation::*;
+pub use color::*;
+pub use frame::*;
+
 use core::{marker::PhantomData, ops::Deref};
 use device_driver::AsyncBufferInterface;
-use embedded_hal_async::i2c::I2c;
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
 
 use crate::ll::{self, DeviceError};
 
 pub use crate::ll::MaxCurrentOption;
 
+/// Largest number of RGB LEDs of any variant, used to size buffers on the stack.
+///
+/// Array lengths cannot depend on the variant, so buffers are sized for the largest variant
+/// and sliced to the `RGB_COUNT` or `LED_COUNT` of the actual variant.
+pub(crate) const MAX_RGB_COUNT: usize = 12;
+/// Largest number of OUT channels of any variant, three per RGB LED.
+pub(crate) const MAX_LED_COUNT: usize = MAX_RGB_COUNT * 3;
+
+impl MaxCurrentOption {
+    /// Option for a maximum current of `milliamps`, where 25.5mA is expressed as `25`.
+    ///
+    /// Returns `None` for currents the device does not support.
+    pub const fn from_milliamps(milliamps: u16) -> Option<Self> {
+        match milliamps {
+            25 => Some(MaxCurrentOption::Current25MA5),
+            35 => Some(MaxCurrentOption::Current35MA),
+            _ => None,
+        }
+    }
+}
+
+/// Level an address pin of the device is tied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum PinLevel {
+    Gnd,
+    Vcc,
+}
+
 /// I2C address used to address the device.
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 pub enum Address {
-    /// Devices configured with `GND, GND` on addr0 and addr1.
+    /// Devices configured with addr0 on `GND` and addr1 on `GND`.
     #[default]
     Address0,
-    /// Devices configured with `GND, VCC` on addr0 and addr1.
+    /// Devices configured with addr0 on `VCC` and addr1 on `GND`.
     Address1,
-    /// Devices configured with `VCC, GND` on addr0 and addr1.
+    /// Devices configured with addr0 on `GND` and addr1 on `VCC`.
     Address2,
-    /// Devices configured with `VCC, VCC` on addr0 and addr1.
+    /// Devices configured with addr0 on `VCC` and addr1 on `VCC`.
     Address3,
     /// Broadcast address to address all similar devices on the I2C bus.
     ///
     /// Is only valid if all devices would respond to the same commands in a similar way.
     /// In other words: if all devices are configured identically.
+    /// Methods reading from the device return [Error::BroadcastRead].
     Broadcast,
 }
 
@@ -38,6 +83,35 @@ pub enum Error<T> {
     /// For example: when you index RGB LED #11 for the LP5030,
     /// which only has up to RGB LED #9.
     Index,
+    /// A register was read through a driver constructed with [Address::Broadcast].
+    ///
+    /// All devices respond to reads on the broadcast address at once, making the result meaningless.
+    BroadcastRead,
+    /// The configured maximum current is not valid for the declared supply voltage.
+    ///
+    /// For example: 35mA with `Vcc` below 3.3V.
+    CurrentExceedsSupply,
+}
+
+impl<T> core::fmt::Display for Error<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Error::Interface(_) => "I2C interface error",
+            Error::Index => "LED/RGB index out of range",
+            Error::BroadcastRead => "read through the broadcast address",
+            Error::CurrentExceedsSupply => "maximum current not valid for the supply voltage",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: std::error::Error + 'static> std::error::Error for Error<T> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Interface(e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 impl<T> From<DeviceError<T>> for Error<T> {
@@ -51,8 +125,13 @@ impl<T> From<DeviceError<T>> for Error<T> {
 
 /// Color value for an RGB LED, with each `u8` representing the 8-bit value for
 /// the Red, Green and Blue channels.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// Colors are ordered lexicographically by channel: first by Red, then by Green, then by Blue.
+///
+/// Serialized as an array of the three channel values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rgb(pub [u8; 3]);
 
 impl core::ops::Deref for Rgb {
@@ -75,16 +154,43 @@ impl From<(u8, u8, u8)> for Rgb {
     }
 }
 
+/// Hex color code in the form `0xRRGGBB`, the most significant byte is ignored.
+impl From<u32> for Rgb {
+    fn from(value: u32) -> Self {
+        Rgb::from_hex(value)
+    }
+}
+
+#[cfg(feature = "rgb")]
+impl From<rgb::RGB8> for Rgb {
+    fn from(value: rgb::RGB8) -> Self {
+        Rgb([value.r, value.g, value.b])
+    }
+}
+
+#[cfg(feature = "rgb")]
+impl From<Rgb> for rgb::RGB8 {
+    fn from(value: Rgb) -> Self {
+        let [r, g, b] = value.0;
+        rgb::RGB8 { r, g, b }
+    }
+}
+
 /// Markers used to indicated the typestate of the device.
 pub mod marker {
     pub struct Standby;
     pub struct Normal;
+    /// Normal mode with all RGB LEDs controlled by the bank registers.
+    pub struct Bank;
 
     trait Sealed {}
 
     #[allow(private_bounds)]
     pub trait Marker: Sealed {}
 
+    /// Typestates in which RGB LEDs can be controlled individually.
+    pub trait Individual: Marker {}
+
     macro_rules! impl_marker {
         ($struct:ty) => {
             impl Sealed for $struct {}
@@ -94,6 +200,10 @@ pub mod marker {
 
     impl_marker!(Standby);
     impl_marker!(Normal);
+    impl_marker!(Bank);
+
+    impl Individual for Standby {}
+    impl Individual for Normal {}
 }
 
 /// High level generic driver for the LP50xx family of devices.
@@ -101,14 +211,29 @@ pub mod marker {
 /// See [LP50xx] on how to instantiate the device.
 ///
 /// The channels can be configured per OUT and per RGB LED.
-/// Bank-mode is not (yet) supported.
-pub struct Driver<VARIANT: LP50xx, T: I2c, STATE: marker::Marker> {
-    device: ll::Device<ll::i2c::DeviceInterface<T>>,
+/// Bank-mode is supported using [Driver::set_bank_atomic] for a subset of the RGB LEDs,
+/// or using [Driver::into_bank_mode] for all RGB LEDs.
+pub struct Driver<
+    VARIANT: LP50xx,
+    T: I2c,
+    STATE: marker::Marker,
+    const N: usize = { ll::i2c::MAX_WRITE_SIZE },
+> {
+    device: ll::Device<ll::i2c::BufferedDeviceInterface<T, N>>,
+    correction: Option<&'static [Rgb; 256]>,
+    /// Constructed with [Address::Broadcast], for which reads are meaningless.
+    broadcast: bool,
+    /// Values last written to the `OUTx_COLOR` registers, to warn about dark RGB LEDs without reading back.
+    #[cfg(all(debug_assertions, any(feature = "log", feature = "defmt-03")))]
+    written_colors: [u8; MAX_LED_COUNT],
     marker: PhantomData<VARIANT>,
     state: PhantomData<STATE>,
 }
 
 /// Generic configuration for an LP50xx device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Config {
     /// Use logarithmic scaling.
     pub log_scale: bool,
@@ -120,6 +245,128 @@ pub struct Config {
     ///
     /// 35mA only valid when `Vcc >= 3.3V`.
     pub max_current: ll::MaxCurrentOption,
+    /// Declared supply voltage `Vcc` in millivolts, used to validate `max_current`.
+    ///
+    /// `None` skips this validation.
+    pub supply_voltage_mv: Option<u16>,
+}
+
+/// Builder for [Config], starting from [Config::default].
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self {
+            config: Config::default(),
+        }
+    }
+
+    /// See [Config::log_scale].
+    pub fn log_scale(mut self, log_scale: bool) -> Self {
+        self.config.log_scale = log_scale;
+        self
+    }
+
+    /// See [Config::power_save].
+    pub fn power_save(mut self, power_save: bool) -> Self {
+        self.config.power_save = power_save;
+        self
+    }
+
+    /// See [Config::pwm_dithering].
+    pub fn pwm_dithering(mut self, pwm_dithering: bool) -> Self {
+        self.config.pwm_dithering = pwm_dithering;
+        self
+    }
+
+    /// See [Config::max_current].
+    pub fn max_current(mut self, max_current: ll::MaxCurrentOption) -> Self {
+        self.config.max_current = max_current;
+        self
+    }
+
+    /// See [Config::supply_voltage_mv].
+    pub fn supply_voltage_mv(mut self, supply_voltage_mv: u16) -> Self {
+        self.config.supply_voltage_mv = Some(supply_voltage_mv);
+        self
+    }
+
+    pub fn build(self) -> Config {
+        self.config
+    }
+}
+
+/// Thermal properties of a board, used by [thermal_limit_brightness].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ThermalDesign {
+    /// Junction-to-ambient thermal resistance `RθJA` in °C/W.
+    ///
+    /// See the thermal information in the datasheet for the package, or measure it on the actual board.
+    pub theta_ja_c_per_w: u16,
+    /// Supply voltage of the LEDs in millivolts.
+    pub led_supply_mv: u16,
+    /// Forward voltage of the LEDs at the maximum current in millivolts.
+    ///
+    /// The remainder of the LED supply voltage drops over the current sinks, and is dissipated in the device.
+    pub led_forward_mv: u16,
+    /// Number of OUT channels with an LED connected.
+    pub channels: u8,
+}
+
+/// Maximum brightness at an ambient temperature of `ambient_c` degrees Celsius,
+/// keeping the junction temperature below the 150°C limit of the device.
+///
+/// The dissipation at full load is the voltage over the current sinks times `max_current`, for all channels,
+/// and the junction temperature rises above ambient by that dissipation times `RθJA`.
+/// Assumes all colors at full scale and linear scaling, such that the dissipation is proportional to the brightness.
+/// The result can be used as a cap for [Driver::set_all_brightness] to throttle the output when running hot.
+pub fn thermal_limit_brightness(
+    ambient_c: i16,
+    max_current: ll::MaxCurrentOption,
+    design: &ThermalDesign,
+) -> u8 {
+    const JUNCTION_MAX_C: i64 = 150;
+
+    let current_ua: i64 = match max_current {
+        ll::MaxCurrentOption::Current25MA5 => 25_500,
+        ll::MaxCurrentOption::Current35MA => 35_000,
+    };
+    let headroom_mv = design.led_supply_mv.saturating_sub(design.led_forward_mv) as i64;
+    let dissipation_uw = design.channels as i64 * current_ua * headroom_mv / 1000;
+    let full_load_rise_mc = dissipation_uw * design.theta_ja_c_per_w as i64 / 1000;
+
+    let headroom_mc = (JUNCTION_MAX_C - ambient_c as i64).max(0) * 1000;
+    if full_load_rise_mc == 0 {
+        return if headroom_mc > 0 { 255 } else { 0 };
+    }
+    (headroom_mc * 255 / full_load_rise_mc).min(255) as u8
+}
+
+/// Decoded state of the device configuration registers, see [Driver::config_report].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ConfigReport {
+    /// The device is enabled (in Normal mode).
+    pub enabled: bool,
+    /// Logarithmic scaling is used.
+    pub log_scale: bool,
+    /// Automatic power save mode is enabled.
+    pub power_save: bool,
+    /// PWM dithering is enabled.
+    pub pwm_dithering: bool,
+    /// The maximum amount of current for a single LED channel.
+    pub max_current: ll::MaxCurrentOption,
+    /// Register addresses are incremented automatically for consecutive reads and writes.
+    pub auto_increment: bool,
 }
 
 impl Default for Config {
@@ -129,22 +376,87 @@ impl Default for Config {
             power_save: true,
             pwm_dithering: true,
             max_current: ll::MaxCurrentOption::Current25MA5,
+            supply_voltage_mv: None,
         }
     }
 }
 
-impl<VARIANT: LP50xx, T: I2c> Driver<VARIANT, T, marker::Standby> {
-    fn new(interface: T, address: Address) -> Self {
-        let address = match address {
+impl Config {
+    /// Start building a configuration from the defaults.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+
+    /// Preset for the lowest power consumption with smooth dimming.
+    ///
+    /// Uses logarithmic scaling, power saving and PWM dithering at a maximum current of 25.5mA.
+    pub const fn high_efficiency() -> Self {
+        Self {
+            log_scale: true,
+            power_save: true,
+            pwm_dithering: true,
+            max_current: ll::MaxCurrentOption::Current25MA5,
+            supply_voltage_mv: None,
+        }
+    }
+
+    /// Preset for the highest brightness.
+    ///
+    /// Uses a maximum current of 35mA, which is only valid when `Vcc >= 3.3V`.
+    /// Power saving is disabled to avoid wake-up delays, logarithmic scaling and PWM dithering are enabled.
+    pub const fn max_brightness() -> Self {
+        Self {
+            log_scale: true,
+            power_save: false,
+            pwm_dithering: true,
+            max_current: ll::MaxCurrentOption::Current35MA,
+            supply_voltage_mv: None,
+        }
+    }
+}
+
+impl Address {
+    /// Address of a device with its addr0 and addr1 pins tied to the given levels.
+    ///
+    /// The addr0 pin sets the least significant bit of the I2C address, addr1 the bit above it.
+    pub const fn from_pins(addr0: PinLevel, addr1: PinLevel) -> Self {
+        match (addr0, addr1) {
+            (PinLevel::Gnd, PinLevel::Gnd) => Address::Address0,
+            (PinLevel::Vcc, PinLevel::Gnd) => Address::Address1,
+            (PinLevel::Gnd, PinLevel::Vcc) => Address::Address2,
+            (PinLevel::Vcc, PinLevel::Vcc) => Address::Address3,
+        }
+    }
+
+    /// The 7-bit I2C address of a device of the given variant, as used on the wire.
+    ///
+    /// Usable in const contexts, unlike [LP50xx::i2c_address].
+    pub const fn i2c_address<VARIANT: LP50xx>(self) -> u8 {
+        match self {
             Address::Address0 => VARIANT::I2C_ADDRESS_BASE,
             Address::Address1 => VARIANT::I2C_ADDRESS_BASE | 0b01,
             Address::Address2 => VARIANT::I2C_ADDRESS_BASE | 0b10,
             Address::Address3 => VARIANT::I2C_ADDRESS_BASE | 0b11,
             Address::Broadcast => VARIANT::I2C_ADDRESS_BROADCAST,
+        }
+    }
+}
+
+impl<VARIANT: LP50xx, T: I2c, const N: usize> Driver<VARIANT, T, marker::Standby, N> {
+    fn new(interface: ll::i2c::BufferedDeviceInterface<T, N>, broadcast: bool) -> Self {
+        const {
+            assert!(
+                VARIANT::RGB_COUNT as usize <= MAX_RGB_COUNT,
+                "buffers must fit all RGB LEDs of the variant"
+            )
         };
 
         Self {
-            device: ll::Device::new(ll::i2c::DeviceInterface::new(interface, address)),
+            device: ll::Device::new(interface),
+            correction: None,
+            broadcast,
+            #[cfg(all(debug_assertions, any(feature = "log", feature = "defmt-03")))]
+            written_colors: [0; MAX_LED_COUNT],
             marker: PhantomData,
             state: PhantomData,
         }
@@ -156,21 +468,17 @@ impl<VARIANT: LP50xx, T: I2c> Driver<VARIANT, T, marker::Standby> {
     /// This will consume up to 10mA of current, unless power saving is enabled.
     pub async fn enable(
         mut self,
-    ) -> Result<Driver<VARIANT, T, marker::Normal>, DeviceError<T::Error>> {
+    ) -> Result<Driver<VARIANT, T, marker::Normal, N>, DeviceError<T::Error>> {
         self.device
             .device_config_0()
             .write_async(|w| w.set_chip_en(true))
             .await?;
 
-        Ok(Driver {
-            device: self.device,
-            marker: PhantomData,
-            state: PhantomData,
-        })
+        Ok(self.into_state())
     }
 }
 
-impl<VARIANT: LP50xx, T: I2c> Driver<VARIANT, T, marker::Normal> {
+impl<VARIANT: LP50xx, T: I2c, const N: usize> Driver<VARIANT, T, marker::Normal, N> {
     /// Disable the device, putting it into Standby mode.
     ///
     /// All register values will be retained, but the constant current sinks will no longer
@@ -179,48 +487,458 @@ impl<VARIANT: LP50xx, T: I2c> Driver<VARIANT, T, marker::Normal> {
     /// Consumes up to 12uA of current, depending on the device type.
     pub async fn disable(
         mut self,
-    ) -> Result<Driver<VARIANT, T, marker::Standby>, DeviceError<T::Error>> {
+    ) -> Result<Driver<VARIANT, T, marker::Standby, N>, DeviceError<T::Error>> {
         self.device
             .device_config_0()
             .write_async(|w| w.set_chip_en(false))
             .await?;
 
-        Ok(Driver {
+        Ok(self.into_state())
+    }
+
+    /// Disable the device like [Driver::disable], then wait for the current sinks to settle.
+    ///
+    /// Prevents enabling the device again while it is still shutting down,
+    /// for example when reconfiguring it on the fly.
+    pub async fn disable_and_wait(
+        self,
+        delay: &mut impl DelayNs,
+    ) -> Result<Driver<VARIANT, T, marker::Standby, N>, DeviceError<T::Error>> {
+        /// Time for the current sinks to settle after disabling the device.
+        const SETTLE_DELAY_US: u32 = 1000;
+
+        let driver = self.disable().await?;
+        delay.delay_us(SETTLE_DELAY_US).await;
+        Ok(driver)
+    }
+
+    /// Put all RGB LEDs under bank control.
+    ///
+    /// Individual control of the RGB LEDs is not available in bank mode.
+    pub async fn into_bank_mode(
+        mut self,
+    ) -> Result<Driver<VARIANT, T, marker::Bank, N>, Error<T::Error>> {
+        self.write_led_config(u16::MAX).await?;
+        Ok(self.into_state())
+    }
+}
+
+impl<VARIANT: LP50xx, T: I2c, const N: usize> Driver<VARIANT, T, marker::Bank, N> {
+    /// Set the brightness of all RGB LEDs under bank control, acting as a master dimmer.
+    pub async fn set_bank_brightness(&mut self, value: u8) -> Result<(), Error<T::Error>> {
+        self.write_block(VARIANT::BANK_BRIGHTNESS_ADDRESS, &[value])
+            .await
+    }
+
+    /// Set the color of all RGB LEDs under bank control.
+    ///
+    /// Writes the `BANK_A_COLOR` up to `BANK_C_COLOR` registers in a single transaction.
+    pub async fn set_bank_color(&mut self, value: impl Into<Rgb>) -> Result<(), Error<T::Error>> {
+        let value = self.correct(value.into());
+
+        self.write_block(VARIANT::BANK_BRIGHTNESS_ADDRESS + 1, value.deref())
+            .await
+    }
+
+    /// Return all RGB LEDs to individual control.
+    pub async fn exit_bank_mode(
+        mut self,
+    ) -> Result<Driver<VARIANT, T, marker::Normal, N>, Error<T::Error>> {
+        self.write_led_config(0).await?;
+        Ok(self.into_state())
+    }
+}
+
+impl<VARIANT: LP50xx, T: I2c, MARKER: marker::Marker, const N: usize>
+    Driver<VARIANT, T, MARKER, N>
+{
+    fn into_state<STATE: marker::Marker>(self) -> Driver<VARIANT, T, STATE, N> {
+        Driver {
             device: self.device,
+            correction: self.correction,
+            broadcast: self.broadcast,
+            #[cfg(all(debug_assertions, any(feature = "log", feature = "defmt-03")))]
+            written_colors: self.written_colors,
             marker: PhantomData,
             state: PhantomData,
-        })
+        }
+    }
+
+    /// Reset all registers to their default values, putting the device into Standby mode.
+    ///
+    /// The color correction table is retained.
+    pub async fn reset(
+        mut self,
+    ) -> Result<Driver<VARIANT, T, marker::Standby, N>, Error<T::Error>> {
+        self.write_block(VARIANT::RESET_ADDRESS, &[0xFF]).await?;
+        #[cfg(all(debug_assertions, any(feature = "log", feature = "defmt-03")))]
+        {
+            self.written_colors = [0; MAX_LED_COUNT];
+        }
+        Ok(self.into_state())
+    }
+
+    /// Consume the driver, returning the underlying I2C peripheral.
+    pub fn into_inner(self) -> T {
+        self.device.interface.into_inner()
+    }
+
+    /// Number of OUT channels of the device.
+    pub const fn led_count(&self) -> u8 {
+        VARIANT::LED_COUNT
+    }
+
+    /// Number of RGB LEDs of the device, each consisting of three OUT channels.
+    pub const fn rgb_count(&self) -> u8 {
+        VARIANT::RGB_COUNT
+    }
+
+    /// Theoretical time in microseconds to write the colors of all RGB LEDs at an I2C bus speed of `i2c_hz`.
+    ///
+    /// Takes the transactions into account that a full frame is split into,
+    /// each consisting of a start and stop condition, the device address, the register address and the data.
+    /// Useful to budget the frame rate of animations.
+    /// Does not account for clock stretching or delays between transactions.
+    ///
+    /// Panics if `i2c_hz` is zero.
+    pub fn frame_time_us(&self, i2c_hz: u32) -> u32 {
+        const BITS_PER_BYTE: u64 = 9; // Including ACK.
+        const START_STOP_BITS: u64 = 2;
+
+        let bytes = VARIANT::LED_COUNT as u64;
+        let transactions = self.chunks_needed(VARIANT::LED_COUNT as usize) as u64;
+
+        let bits = transactions * (START_STOP_BITS + 2 * BITS_PER_BYTE) + bytes * BITS_PER_BYTE;
+        (bits * 1_000_000).div_ceil(i2c_hz as u64) as u32
+    }
+
+    /// Number of I2C transactions a write of `byte_len` consecutive register values is split into.
+    ///
+    /// Depends on the maximum transfer size, see [Driver::set_max_i2c_transfer].
+    /// Useful for timing-critical code that must stay within a transaction budget.
+    pub fn chunks_needed(&self, byte_len: usize) -> usize {
+        byte_len.div_ceil(self.device.interface.max_transfer() - 1)
+    }
+
+    /// Apply the color correction table to a color value, if any is configured.
+    fn correct(&self, value: Rgb) -> Rgb {
+        match self.correction {
+            Some(lut) => Rgb([
+                lut[value[0] as usize][0],
+                lut[value[1] as usize][1],
+                lut[value[2] as usize][2],
+            ]),
+            None => value,
+        }
+    }
+
+    /// Apply the color correction table to the values of consecutive OUT channels, starting at `channel_i`.
+    fn correct_channels(&self, channel_i: u8, data: &mut [u8]) {
+        if let Some(lut) = self.correction {
+            for (i, value) in data.iter_mut().enumerate() {
+                *value = lut[*value as usize][(channel_i as usize + i) % 3];
+            }
+        }
+    }
+
+    /// Write consecutive registers, split into as many transactions as required.
+    async fn write_block(
+        &mut self,
+        mut address: u8,
+        mut data: &[u8],
+    ) -> Result<(), Error<T::Error>> {
+        // Note: auto incrementing is enabled.
+        while !data.is_empty() {
+            let written = self.device.interface().write(address, data).await?;
+            #[cfg(all(debug_assertions, any(feature = "log", feature = "defmt-03")))]
+            self.shadow_colors(address, &data[..written]);
+            address += written as u8;
+            data = &data[written..];
+        }
+        Ok(())
+    }
+
+    /// Keep track of the values written to the `OUTx_COLOR` registers, see [Driver::set_rgb_brightness].
+    #[cfg(all(debug_assertions, any(feature = "log", feature = "defmt-03")))]
+    fn shadow_colors(&mut self, address: u8, data: &[u8]) {
+        let out = VARIANT::OUT_START_ADDRESS as usize
+            ..(VARIANT::OUT_START_ADDRESS + VARIANT::LED_COUNT) as usize;
+        for (register, value) in (address as usize..).zip(data) {
+            if out.contains(&register) {
+                self.written_colors[register - out.start] = *value;
+            }
+        }
+    }
+
+    /// Write the colors of consecutive RGB LEDs starting at `rgb_i`, applying color correction.
+    ///
+    /// Will return the [Error::Index] if the device does not have all indexed RGB LEDs.
+    async fn write_rgbs(&mut self, rgb_i: u8, colors: &[Rgb]) -> Result<(), Error<T::Error>> {
+        if rgb_i as usize + colors.len() > VARIANT::RGB_COUNT as usize {
+            return Err(Error::Index);
+        }
+
+        let mut buf = [0u8; MAX_LED_COUNT];
+        let buf = &mut buf[..colors.len() * 3];
+        for (chunk, value) in buf.chunks_exact_mut(3).zip(colors) {
+            chunk.copy_from_slice(self.correct(*value).deref());
+        }
+
+        self.write_block(VARIANT::OUT_START_ADDRESS + rgb_i * 3, buf)
+            .await
+    }
+
+    /// Write the `LED_CONFIG` registers, with bit `n` of `mask` enabling bank control for RGB LED `n`.
+    ///
+    /// Bits for RGB LEDs the device does not have are ignored.
+    async fn write_led_config(&mut self, mask: u16) -> Result<(), Error<T::Error>> {
+        let config_count =
+            (VARIANT::BANK_BRIGHTNESS_ADDRESS - VARIANT::LED_CONFIG_START_ADDRESS) as usize;
+        let mask = mask & ((1 << VARIANT::RGB_COUNT) - 1);
+
+        self.write_block(
+            VARIANT::LED_CONFIG_START_ADDRESS,
+            &mask.to_le_bytes()[..config_count],
+        )
+        .await
+    }
+
+    /// Read consecutive registers.
+    ///
+    /// Will return the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast].
+    async fn read_block(&mut self, address: u8, buf: &mut [u8]) -> Result<(), Error<T::Error>> {
+        if self.broadcast {
+            return Err(Error::BroadcastRead);
+        }
+
+        // Note: auto incrementing is enabled.
+        self.device.interface().read(address, buf).await?;
+        Ok(())
+    }
+
+    /// Limit the number of bytes in a single I2C write, including the register address.
+    ///
+    /// Writes spanning multiple registers are split into more, smaller transactions to respect this limit,
+    /// for I2C controllers that cap the transaction length.
+    /// Defaults to and is capped at the scratch buffer size (see [LP50xx::new_with_buffer]), and is at least 2 bytes.
+    pub fn set_max_i2c_transfer(&mut self, max_transfer: usize) {
+        self.device.interface().set_max_transfer(max_transfer);
+    }
+
+    /// Set a color correction table, applied to each color channel before it is written.
+    ///
+    /// Each channel value is replaced by the matching channel of the table entry at that value.
+    /// For example: a red value of `10` is written as `lut[10][0]`.
+    /// Can be used to compensate for the non-linear response of specific LEDs,
+    /// generalizing gamma correction and white-balancing into an arbitrary curve.
+    ///
+    /// Pass `None` to disable correction, which is the default.
+    pub fn set_correction_lut(&mut self, lut: Option<&'static [Rgb; 256]>) {
+        self.correction = lut;
     }
-}
 
-impl<VARIANT: LP50xx, T: I2c, MARKER: marker::Marker> Driver<VARIANT, T, MARKER> {
     /// Set the general configuration parameters of the device.
-    pub async fn configure(&mut self, config: &Config) -> Result<(), DeviceError<T::Error>> {
+    ///
+    /// When constructed with [Address::Broadcast], the register is written without reading it back first,
+    /// resetting `LED_Global_Off` and enabling auto incrementing.
+    ///
+    /// Will return the [Error::CurrentExceedsSupply] if `max_current` is not valid for the declared supply voltage.
+    pub async fn configure(&mut self, config: &Config) -> Result<(), Error<T::Error>> {
+        const MIN_SUPPLY_35MA_MV: u16 = 3300;
+
+        if config.max_current == ll::MaxCurrentOption::Current35MA
+            && config
+                .supply_voltage_mv
+                .is_some_and(|mv| mv < MIN_SUPPLY_35MA_MV)
+        {
+            return Err(Error::CurrentExceedsSupply);
+        }
+
+        let apply = |w: &mut ll::field_sets::DeviceConfig1| {
+            w.set_log_scale_en(config.log_scale);
+            w.set_max_current_option(config.max_current);
+            w.set_power_save_en(config.power_save);
+            w.set_pwm_dithering_en(config.pwm_dithering);
+        };
+
+        // Note: reading back over the broadcast address is meaningless, thus the complete register is written,
+        // with the other fields at their defaults: auto incrementing enabled, and LED_Global_Off cleared.
+        if self.broadcast {
+            self.device.device_config_1().write_async(apply).await?;
+        } else {
+            self.device.device_config_1().modify_async(apply).await?;
+        }
+        Ok(())
+    }
+
+    /// Enable or disable logarithmic scaling, leaving the other configuration parameters untouched.
+    ///
+    /// Will return the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast],
+    /// as the other configuration parameters are read back first.
+    pub async fn set_log_scale(&mut self, enabled: bool) -> Result<(), Error<T::Error>> {
+        if self.broadcast {
+            return Err(Error::BroadcastRead);
+        }
+
+        self.device
+            .device_config_1()
+            .modify_async(|w| w.set_log_scale_en(enabled))
+            .await?;
+        Ok(())
+    }
+
+    /// Enable or disable automatic power save mode, leaving the other configuration parameters untouched.
+    ///
+    /// Will return the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast],
+    /// as the other configuration parameters are read back first.
+    pub async fn set_power_save(&mut self, enabled: bool) -> Result<(), Error<T::Error>> {
+        if self.broadcast {
+            return Err(Error::BroadcastRead);
+        }
+
+        self.device
+            .device_config_1()
+            .modify_async(|w| w.set_power_save_en(enabled))
+            .await?;
+        Ok(())
+    }
+
+    /// Enable or disable PWM dithering, leaving the other configuration parameters untouched.
+    ///
+    /// Will return the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast],
+    /// as the other configuration parameters are read back first.
+    pub async fn set_pwm_dithering(&mut self, enabled: bool) -> Result<(), Error<T::Error>> {
+        if self.broadcast {
+            return Err(Error::BroadcastRead);
+        }
+
+        self.device
+            .device_config_1()
+            .modify_async(|w| w.set_pwm_dithering_en(enabled))
+            .await?;
+        Ok(())
+    }
+
+    /// Set the maximum amount of current for a single LED channel, leaving the other configuration parameters untouched.
+    ///
+    /// 35mA only valid when `Vcc >= 3.3V`.
+    ///
+    /// Will return the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast],
+    /// as the other configuration parameters are read back first.
+    pub async fn set_max_current(
+        &mut self,
+        option: ll::MaxCurrentOption,
+    ) -> Result<(), Error<T::Error>> {
+        if self.broadcast {
+            return Err(Error::BroadcastRead);
+        }
+
         self.device
             .device_config_1()
-            .modify_async(|w| {
-                w.set_log_scale_en(config.log_scale);
-                w.set_max_current_option(config.max_current);
-                w.set_power_save_en(config.power_save);
-                w.set_pwm_dithering_en(config.pwm_dithering);
-            })
+            .modify_async(|w| w.set_max_current_option(option))
             .await?;
         Ok(())
     }
 
+    /// Read back the device configuration registers, for example to include in a bug report.
+    ///
+    /// Will return the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast].
+    pub async fn config_report(&mut self) -> Result<ConfigReport, Error<T::Error>> {
+        if self.broadcast {
+            return Err(Error::BroadcastRead);
+        }
+
+        let config0 = self.device.device_config_0().read_async().await?;
+        let config1 = self.device.device_config_1().read_async().await?;
+
+        Ok(ConfigReport {
+            enabled: config0.chip_en(),
+            log_scale: config1.log_scale_en(),
+            power_save: config1.power_save_en(),
+            pwm_dithering: config1.pwm_dithering_en(),
+            max_current: config1.max_current_option(),
+            auto_increment: config1.auto_incr_en(),
+        })
+    }
+
+    /// Read back the general configuration parameters of the device.
+    ///
+    /// Useful to verify that [Driver::configure] took effect, for example after an unexpected reset.
+    ///
+    /// Will return the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast].
+    pub async fn read_config(&mut self) -> Result<Config, Error<T::Error>> {
+        if self.broadcast {
+            return Err(Error::BroadcastRead);
+        }
+
+        let config1 = self.device.device_config_1().read_async().await?;
+
+        Ok(Config {
+            log_scale: config1.log_scale_en(),
+            power_save: config1.power_save_en(),
+            pwm_dithering: config1.pwm_dithering_en(),
+            max_current: config1.max_current_option(),
+            supply_voltage_mv: None,
+        })
+    }
+}
+
+impl<VARIANT: LP50xx, T: I2c, STATE: marker::Individual, const N: usize>
+    Driver<VARIANT, T, STATE, N>
+{
     /// Set the specific OUT channel to a specific color value.
     ///
     /// Will return the [Error::Index] if the device does not have the indexed channel.
     pub async fn set_channel(&mut self, channel_i: u8, value: u8) -> Result<(), Error<T::Error>> {
-        if channel_i > VARIANT::LED_COUNT {
+        if channel_i >= VARIANT::LED_COUNT {
             return Err(Error::Index);
         }
 
-        self.device
-            .interface()
-            .write(VARIANT::OUT_START_ADDRESS + channel_i, &[value])
+        let mut buf = [value];
+        self.correct_channels(channel_i, &mut buf);
+
+        self.write_block(VARIANT::OUT_START_ADDRESS + channel_i, &buf)
+            .await
+    }
+
+    /// Set consecutive OUT channels, starting at `start_i`, to specific color values.
+    ///
+    /// Useful when each OUT drives an independent single-color LED.
+    ///
+    /// Will return the [Error::Index] if the device does not have all indexed channels.
+    pub async fn set_channels(
+        &mut self,
+        start_i: u8,
+        values: &[u8],
+    ) -> Result<(), Error<T::Error>> {
+        if start_i as usize + values.len() > VARIANT::LED_COUNT as usize {
+            return Err(Error::Index);
+        }
+
+        let mut buf = [0u8; MAX_LED_COUNT];
+        let buf = &mut buf[..values.len()];
+        buf.copy_from_slice(values);
+        self.correct_channels(start_i, buf);
+
+        self.write_block(VARIANT::OUT_START_ADDRESS + start_i, buf)
+            .await
+    }
+
+    /// Read back the value of the specific OUT channel.
+    ///
+    /// Returns the value as written to the device, thus including color correction.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed channel,
+    /// and the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast].
+    pub async fn read_channel(&mut self, channel_i: u8) -> Result<u8, Error<T::Error>> {
+        if channel_i >= VARIANT::LED_COUNT {
+            return Err(Error::Index);
+        }
+
+        let mut buf = [0u8];
+        self.read_block(VARIANT::OUT_START_ADDRESS + channel_i, &mut buf)
             .await?;
-        Ok(())
+        Ok(buf[0])
     }
 
     /// Set the RGB LED color values.
@@ -231,46 +949,382 @@ impl<VARIANT: LP50xx, T: I2c, MARKER: marker::Marker> Driver<VARIANT, T, MARKER>
         rgb_i: u8,
         value: impl Into<Rgb>,
     ) -> Result<(), Error<T::Error>> {
-        if rgb_i > VARIANT::RGB_COUNT {
+        if rgb_i >= VARIANT::RGB_COUNT {
             return Err(Error::Index);
         }
 
-        // Note: auto incrementing is enabled.
-        self.device
-            .interface()
-            .write(VARIANT::OUT_START_ADDRESS + rgb_i * 3, value.into().deref())
+        let value = self.correct(value.into());
+
+        self.write_block(VARIANT::OUT_START_ADDRESS + rgb_i * 3, value.deref())
+            .await
+    }
+
+    /// Set the RGB LED color values, applying gamma correction (see [GAMMA]) to each channel.
+    ///
+    /// Gives more accurate colors at the low end than the logarithmic scaling of the device,
+    /// which only applies to the brightness.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED.
+    pub async fn set_rgb_gamma(
+        &mut self,
+        rgb_i: u8,
+        value: impl Into<Rgb>,
+    ) -> Result<(), Error<T::Error>> {
+        let value = value.into().0.map(|c| GAMMA[c as usize]);
+        self.set_rgb(rgb_i, value).await
+    }
+
+    /// Set the RGB LED color values, with explicit values for the Red, Green and Blue channels.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED.
+    pub async fn set_rgb_channels(
+        &mut self,
+        rgb_i: u8,
+        r: u8,
+        g: u8,
+        b: u8,
+    ) -> Result<(), Error<T::Error>> {
+        self.set_rgb(rgb_i, Rgb([r, g, b])).await
+    }
+
+    /// Set the colors of the RGB LEDs, starting at the first RGB LED.
+    ///
+    /// Written in as few transactions as the scratch buffer allows.
+    /// If fewer colors than RGB LEDs are passed, the remaining RGB LEDs are left untouched.
+    ///
+    /// Will return the [Error::Index] if more colors than RGB LEDs are passed.
+    pub async fn set_all_rgb(&mut self, colors: &[Rgb]) -> Result<(), Error<T::Error>> {
+        self.write_rgbs(0, colors).await
+    }
+
+    /// Set the colors of a contiguous span of RGB LEDs, starting at `start_i`.
+    ///
+    /// Will return the [Error::Index] if the device does not have all indexed RGB LEDs.
+    pub async fn set_rgb_range(
+        &mut self,
+        start_i: u8,
+        colors: &[Rgb],
+    ) -> Result<(), Error<T::Error>> {
+        self.write_rgbs(start_i, colors).await
+    }
+
+    /// Read back the RGB LED color values.
+    ///
+    /// Returns the values as written to the device, thus including color correction.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED,
+    /// and the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast].
+    pub async fn read_rgb(&mut self, rgb_i: u8) -> Result<Rgb, Error<T::Error>> {
+        if rgb_i >= VARIANT::RGB_COUNT {
+            return Err(Error::Index);
+        }
+
+        let mut buf = [0u8; 3];
+        self.read_block(VARIANT::OUT_START_ADDRESS + rgb_i * 3, &mut buf)
             .await?;
-        Ok(())
+        Ok(Rgb(buf))
     }
 
     /// Set the brightness of a RGB LED (not the color).
     ///
-    /// Will return the [Error::Index] if the device does not have the indexed RGB LED
+    /// With the `log` or `defmt-03` feature in debug builds, a warning is emitted when the brightness is non-zero
+    /// while the color last written through this driver is black, as the RGB LED then stays dark.
+    /// No registers are read back for this.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED.
     pub async fn set_rgb_brightness(
         &mut self,
         rgb_i: u8,
         value: u8,
     ) -> Result<(), Error<T::Error>> {
-        if rgb_i > VARIANT::RGB_COUNT {
+        if rgb_i >= VARIANT::RGB_COUNT {
             return Err(Error::Index);
         }
 
-        self.device
-            .interface()
-            .write(VARIANT::LED_START_ADDRESS + rgb_i, &[value])
+        #[cfg(all(debug_assertions, any(feature = "log", feature = "defmt-03")))]
+        if value != 0 && self.written_colors[rgb_i as usize * 3..][..3] == [0; 3] {
+            #[cfg(feature = "log")]
+            log::warn!(
+                "brightness of RGB LED {rgb_i} set while its color is black, it will stay dark"
+            );
+            #[cfg(feature = "defmt-03")]
+            defmt::warn!(
+                "brightness of RGB LED {} set while its color is black, it will stay dark",
+                rgb_i
+            );
+        }
+
+        self.write_block(VARIANT::LED_START_ADDRESS + rgb_i, &[value])
+            .await
+    }
+
+    /// Set both the brightness and the color of a RGB LED.
+    ///
+    /// The brightness and color registers are not contiguous, thus these are two writes:
+    /// the brightness is written first, then the color.
+    /// The RGB LED is thus never shown with the new color at the old brightness,
+    /// but briefly shows the old color at the new brightness.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED.
+    pub async fn set_rgb_full(
+        &mut self,
+        rgb_i: u8,
+        brightness: u8,
+        color: impl Into<Rgb>,
+    ) -> Result<(), Error<T::Error>> {
+        self.set_rgb_brightness(rgb_i, brightness).await?;
+        self.set_rgb(rgb_i, color).await
+    }
+
+    /// Read back the brightness of a RGB LED (not the color).
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED,
+    /// and the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast].
+    pub async fn read_rgb_brightness(&mut self, rgb_i: u8) -> Result<u8, Error<T::Error>> {
+        if rgb_i >= VARIANT::RGB_COUNT {
+            return Err(Error::Index);
+        }
+
+        let mut buf = [0u8];
+        self.read_block(VARIANT::LED_START_ADDRESS + rgb_i, &mut buf)
             .await?;
-        Ok(())
+        Ok(buf[0])
     }
 
     /// Set the brightness of all RGB LEDs (not the color) in one call.
     pub async fn set_all_brightness(&mut self, value: u8) -> Result<(), Error<T::Error>> {
-        let mut buf: heapless::Vec<u8, 36> = heapless::Vec::new();
-        buf.extend(core::iter::repeat_n(value, VARIANT::RGB_COUNT as usize));
+        let buf = [value; MAX_RGB_COUNT];
+        self.write_block(
+            VARIANT::LED_START_ADDRESS,
+            &buf[..VARIANT::RGB_COUNT as usize],
+        )
+        .await
+    }
+
+    /// Show a progress bar over all RGB LEDs.
+    ///
+    /// The first `fraction / 255 * RGB_COUNT` RGB LEDs are set to `on`, the remainder to `off`.
+    /// The RGB LED on the boundary is blended between `off` and `on` proportionally, giving a smooth edge.
+    pub async fn set_progress(
+        &mut self,
+        fraction: u8,
+        on: impl Into<Rgb>,
+        off: impl Into<Rgb>,
+    ) -> Result<(), Error<T::Error>> {
+        let (on, off) = (on.into(), off.into());
+
+        let total = fraction as u16 * VARIANT::RGB_COUNT as u16;
+        let (full, remainder) = (total / 255, (total % 255) as u8);
+
+        let mut frame = [Rgb([0; 3]); MAX_RGB_COUNT];
+        for (i, value) in frame[..VARIANT::RGB_COUNT as usize].iter_mut().enumerate() {
+            *value = match (i as u16).cmp(&full) {
+                core::cmp::Ordering::Less => on,
+                core::cmp::Ordering::Equal => off.lerp(on, remainder),
+                core::cmp::Ordering::Greater => off,
+            };
+        }
+
+        self.write_rgbs(0, &frame[..VARIANT::RGB_COUNT as usize])
+            .await
+    }
+
+    /// Set the brightness and color of all RGB LEDs in one go.
+    ///
+    /// Both `brightness` and `colors` contain one entry per RGB LED.
+    /// For the LP5012, LP5024 and LP5036 the `LEDx_BRIGHTNESS` registers directly precede the `OUTx_COLOR` registers,
+    /// and both are written as a single block, split into as few transactions as possible.
+    /// This never takes more transactions than writing the brightness and colors separately,
+    /// and can save one: for example 3 instead of 4 for an LP5036 with a 17 byte scratch buffer.
+    /// For the LP5009, LP5018 and LP5030 unused registers lie in between,
+    /// and the brightness and colors are written separately.
+    ///
+    /// Will return the [Error::Index] if the length of `brightness` or `colors` does not match the RGB LED count.
+    pub async fn set_frame(
+        &mut self,
+        brightness: &[u8],
+        colors: &[Rgb],
+    ) -> Result<(), Error<T::Error>> {
+        if brightness.len() != VARIANT::RGB_COUNT as usize
+            || colors.len() != VARIANT::RGB_COUNT as usize
+        {
+            return Err(Error::Index);
+        }
+
+        if VARIANT::LED_START_ADDRESS + VARIANT::RGB_COUNT != VARIANT::OUT_START_ADDRESS {
+            self.write_block(VARIANT::LED_START_ADDRESS, brightness)
+                .await?;
+            return self.write_rgbs(0, colors).await;
+        }
+
+        let mut buf: heapless::Vec<u8, { MAX_RGB_COUNT + MAX_LED_COUNT }> = heapless::Vec::new();
+        buf.extend(brightness.iter().copied());
+        buf.extend(colors.iter().flat_map(|color| *self.correct(*color)));
+
+        self.write_block(VARIANT::LED_START_ADDRESS, &buf).await
+    }
+
+    /// Turn all LEDs off by writing zero to all `OUTx_COLOR` registers.
+    ///
+    /// Unlike [Driver::disable] the device is not put into Standby mode, and the brightness is retained.
+    /// The color correction table is not applied.
+    pub async fn off(&mut self) -> Result<(), Error<T::Error>> {
+        self.all_on(0).await
+    }
+
+    /// Set all `OUTx_COLOR` registers to `value`, useful for burn-in and bring-up of a board.
+    ///
+    /// The brightness is not changed, and the color correction table is not applied.
+    pub async fn all_on(&mut self, value: u8) -> Result<(), Error<T::Error>> {
+        let buf = [value; MAX_LED_COUNT];
+        self.write_block(
+            VARIANT::OUT_START_ADDRESS,
+            &buf[..VARIANT::LED_COUNT as usize],
+        )
+        .await
+    }
+
+    /// Light every LED in full white at the given brightness, useful for bring-up and diagnostics.
+    ///
+    /// Writes `brightness` to all `LEDx_BRIGHTNESS` registers and `0xFF` to all `OUTx_COLOR` registers.
+    /// The color correction table is not applied.
+    pub async fn all_white(&mut self, brightness: u8) -> Result<(), Error<T::Error>> {
+        let mut buf = [brightness; MAX_LED_COUNT];
+        self.write_block(
+            VARIANT::LED_START_ADDRESS,
+            &buf[..VARIANT::RGB_COUNT as usize],
+        )
+        .await?;
+
+        buf.fill(0xFF);
+        self.write_block(
+            VARIANT::OUT_START_ADDRESS,
+            &buf[..VARIANT::LED_COUNT as usize],
+        )
+        .await
+    }
+
+    /// Check whether the `OUTx_COLOR` register of the specific OUT channel tracks writes.
+    ///
+    /// Writes two distinct test values and reads each back, which catches a stuck register
+    /// for a suspected bad channel. The original value is restored afterward.
+    /// Returns `false` if either value did not read back as written.
+    /// The color correction table is not applied.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed channel,
+    /// and the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast].
+    pub async fn diagnose_channel(&mut self, channel_i: u8) -> Result<bool, Error<T::Error>> {
+        const TEST_VALUES: [u8; 2] = [0x55, 0xAA];
+
+        if channel_i >= VARIANT::LED_COUNT {
+            return Err(Error::Index);
+        }
+
+        let address = VARIANT::OUT_START_ADDRESS + channel_i;
+        let mut original = [0u8];
+        self.read_block(address, &mut original).await?;
+
+        let mut tracks = true;
+        for value in TEST_VALUES {
+            let mut read = [0u8];
+            self.write_block(address, &[value]).await?;
+            self.read_block(address, &mut read).await?;
+            tracks &= read[0] == value;
+        }
+
+        self.write_block(address, &original).await?;
+        Ok(tracks)
+    }
+
+    /// Put a single RGB LED under bank control, or return it to individual control.
+    ///
+    /// Reads and writes back the `LED_CONFIG` register containing the RGB LED, leaving the other RGB LEDs untouched.
+    ///
+    /// Will return the [Error::Index] if the device does not have the indexed RGB LED,
+    /// and the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast].
+    pub async fn set_led_bank_enable(
+        &mut self,
+        rgb_i: u8,
+        enabled: bool,
+    ) -> Result<(), Error<T::Error>> {
+        if rgb_i >= VARIANT::RGB_COUNT {
+            return Err(Error::Index);
+        }
+
+        let address = VARIANT::LED_CONFIG_START_ADDRESS + rgb_i / 8;
+        let bit = 1 << (rgb_i % 8);
+
+        let mut config = [0u8];
+        self.read_block(address, &mut config).await?;
+        if enabled {
+            config[0] |= bit;
+        } else {
+            config[0] &= !bit;
+        }
+        self.write_block(address, &config).await
+    }
+
+    /// Read back which RGB LEDs are under bank control, with bit `n` set for RGB LED `n`.
+    ///
+    /// Will return the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast].
+    pub async fn read_bank_assignments(&mut self) -> Result<u16, Error<T::Error>> {
+        let config_count =
+            (VARIANT::BANK_BRIGHTNESS_ADDRESS - VARIANT::LED_CONFIG_START_ADDRESS) as usize;
+
+        let mut buf = [0u8; 2];
+        self.read_block(VARIANT::LED_CONFIG_START_ADDRESS, &mut buf[..config_count])
+            .await?;
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    /// Put the RGB LEDs in `mask` under bank control and set the bank color and brightness.
+    ///
+    /// Bit `n` of `mask` enables bank control for RGB LED `n`, all other RGB LEDs are controlled independently.
+    /// All LEDs are turned off using `LED_Global_Off` while the bank registers are written,
+    /// preventing intermediate states from flashing.
+    ///
+    /// If writing the bank registers fails, `LED_Global_Off` is still cleared before returning the error.
+    ///
+    /// Will return the [Error::Index] if `mask` contains RGB LEDs the device does not have,
+    /// and the [Error::BroadcastRead] if the driver was constructed with [Address::Broadcast],
+    /// as toggling `LED_Global_Off` requires reading back the configuration.
+    pub async fn set_bank_atomic(
+        &mut self,
+        mask: u16,
+        color: impl Into<Rgb>,
+        brightness: u8,
+    ) -> Result<(), Error<T::Error>> {
+        if mask >> VARIANT::RGB_COUNT != 0 {
+            return Err(Error::Index);
+        }
+        if self.broadcast {
+            return Err(Error::BroadcastRead);
+        }
+
+        let config_count =
+            (VARIANT::BANK_BRIGHTNESS_ADDRESS - VARIANT::LED_CONFIG_START_ADDRESS) as usize;
+
+        // Note: the LED_CONFIG and BANK registers are contiguous, and auto incrementing is enabled.
+        let mut buf: heapless::Vec<u8, 6> = heapless::Vec::new();
+        buf.extend(mask.to_le_bytes().into_iter().take(config_count));
+        buf.extend([brightness]);
+        buf.extend(*self.correct(color.into()));
 
         self.device
-            .interface()
-            .write(VARIANT::LED_START_ADDRESS, &buf)
+            .device_config_1()
+            .modify_async(|w| w.set_led_global_off(true))
             .await?;
+        let written = self
+            .write_block(VARIANT::LED_CONFIG_START_ADDRESS, &buf)
+            .await;
+        // Note: always try to turn the LEDs back on, otherwise they would stay dark after a failed write.
+        let restored = self
+            .device
+            .device_config_1()
+            .modify_async(|w| w.set_led_global_off(false))
+            .await;
+        written?;
+        restored?;
         Ok(())
     }
 }
@@ -283,14 +1337,89 @@ pub trait LP50xx: Sized {
     const I2C_ADDRESS_BASE: u8;
     const I2C_ADDRESS_BROADCAST: u8;
 
+    /// Register address of `LED_CONFIG0`, followed by `LED_CONFIG1` on devices with more than 8 RGB LEDs.
+    const LED_CONFIG_START_ADDRESS: u8 = 0x02;
+    /// Register address of `BANK_BRIGHTNESS`, followed by `BANK_A_COLOR` up to `BANK_C_COLOR`.
+    const BANK_BRIGHTNESS_ADDRESS: u8 = Self::LED_START_ADDRESS - 4;
     /// Register address of `LED0_BRIGHTNESS`.
     const LED_START_ADDRESS: u8;
     /// Register address of `OUT0_COLOR`.
     const OUT_START_ADDRESS: u8 = Self::LED_START_ADDRESS + Self::RGB_COUNT;
+    /// Register address of `RESET`.
+    const RESET_ADDRESS: u8 = Self::OUT_START_ADDRESS + Self::LED_COUNT;
+
+    /// Maximum number of RGB LEDs written in a single I2C transaction with the default scratch buffer.
+    ///
+    /// Writes spanning more RGB LEDs are split into multiple transactions.
+    /// See [LP50xx::new_with_buffer] to change the scratch buffer size.
+    const MAX_RGB_PER_WRITE: u8 = ((ll::i2c::MAX_WRITE_SIZE - 1) / 3) as u8;
+
+    /// The 7-bit I2C address of this IC variant at `address`, without constructing a driver.
+    fn i2c_address(address: Address) -> u8 {
+        address.i2c_address::<Self>()
+    }
 
     /// Construct the high level driver for a specific IC variant.
     fn new<T: I2c>(interface: T, address: Address) -> Driver<Self, T, marker::Standby> {
-        Driver::new(interface, address)
+        Driver::new(
+            ll::i2c::DeviceInterface::new(interface, address.i2c_address::<Self>()),
+            matches!(address, Address::Broadcast),
+        )
+    }
+
+    /// Construct the high level driver for a specific IC variant, configure it and enable it.
+    ///
+    /// The device is configured before it is enabled, avoiding a flash of LEDs with the previous configuration.
+    #[allow(async_fn_in_trait)]
+    async fn init<T: I2c>(
+        interface: T,
+        address: Address,
+        config: &Config,
+    ) -> Result<Driver<Self, T, marker::Normal>, Error<T::Error>> {
+        let mut driver = Self::new(interface, address);
+        driver.configure(config).await?;
+        Ok(driver.enable().await?)
+    }
+
+    /// Construct the high level driver for a specific IC variant, and bring it into a known configured state.
+    ///
+    /// Resets all registers to their defaults, enables the device, waits for it to start up and applies `config`.
+    /// Use this when the initial state of the device can not be trusted, for example after a reset of only the MCU.
+    #[allow(async_fn_in_trait)]
+    async fn configured<T: I2c>(
+        interface: T,
+        address: Address,
+        config: &Config,
+        delay: &mut impl DelayNs,
+    ) -> Result<Driver<Self, T, marker::Normal>, Error<T::Error>> {
+        /// Time required after enabling the device before it accepts further writes.
+        const STARTUP_DELAY_US: u32 = 500;
+
+        let mut driver = Self::new(interface, address)
+            .reset()
+            .await?
+            .enable()
+            .await?;
+        delay.delay_us(STARTUP_DELAY_US).await;
+        driver.configure(config).await?;
+        Ok(driver)
+    }
+
+    /// Construct the high level driver for a specific IC variant, with a scratch buffer of `N` bytes.
+    ///
+    /// The buffer includes the register address, and limits the number of bytes written in a single I2C transaction.
+    /// A larger buffer trades RAM for fewer transactions when writing many registers at once,
+    /// for example 37 bytes to write all colors of an LP5036 at once.
+    /// The default is 13 bytes, enough to write all brightness registers at once.
+    /// Must be at least 2 bytes.
+    fn new_with_buffer<T: I2c, const N: usize>(
+        interface: T,
+        address: Address,
+    ) -> Driver<Self, T, marker::Standby, N> {
+        Driver::new(
+            ll::i2c::BufferedDeviceInterface::new(interface, address.i2c_address::<Self>()),
+            matches!(address, Address::Broadcast),
+        )
     }
 }
 
@@ -307,6 +1436,7 @@ impl LP50xx for LP5009 {
     const I2C_ADDRESS_BROADCAST: u8 = 0b0011100;
     const LED_START_ADDRESS: u8 = 0x07;
     const OUT_START_ADDRESS: u8 = 0x0b;
+    const RESET_ADDRESS: u8 = 0x17;
 }
 
 impl LP50xx for LP5012 {
@@ -322,6 +1452,7 @@ impl LP50xx for LP5018 {
     const I2C_ADDRESS_BROADCAST: u8 = 0b0111100;
     const LED_START_ADDRESS: u8 = 0x07;
     const OUT_START_ADDRESS: u8 = 0x0f;
+    const RESET_ADDRESS: u8 = 0x27;
 }
 
 impl LP50xx for LP5024 {
@@ -337,6 +1468,7 @@ impl LP50xx for LP5030 {
     const I2C_ADDRESS_BROADCAST: u8 = 0b0011100;
     const LED_START_ADDRESS: u8 = 0x08;
     const OUT_START_ADDRESS: u8 = 0x14;
+    const RESET_ADDRESS: u8 = 0x38;
 }
 
 impl LP50xx for LP5036 {
@@ -345,3 +1477,63 @@ impl LP50xx for LP5036 {
     const I2C_ADDRESS_BROADCAST: u8 = 0b0011100;
     const LED_START_ADDRESS: u8 = 0x08;
 }
+
+/// Runtime representation of the variants of the LP50xx family.
+///
+/// Complements the type-level [LP50xx] trait for situations where the variant is only known at runtime,
+/// like host tooling or configuration interfaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum Variant {
+    Lp5009,
+    Lp5012,
+    Lp5018,
+    Lp5024,
+    Lp5030,
+    Lp5036,
+}
+
+macro_rules! variant_const {
+    ($self:expr, $const:ident) => {
+        match $self {
+            Variant::Lp5009 => LP5009::$const,
+            Variant::Lp5012 => LP5012::$const,
+            Variant::Lp5018 => LP5018::$const,
+            Variant::Lp5024 => LP5024::$const,
+            Variant::Lp5030 => LP5030::$const,
+            Variant::Lp5036 => LP5036::$const,
+        }
+    };
+}
+
+impl Variant {
+    /// All supported variants, in order of channel count.
+    pub const ALL: [Variant; 6] = [
+        Variant::Lp5009,
+        Variant::Lp5012,
+        Variant::Lp5018,
+        Variant::Lp5024,
+        Variant::Lp5030,
+        Variant::Lp5036,
+    ];
+
+    /// Number of OUT channels.
+    pub const fn led_count(self) -> u8 {
+        variant_const!(self, LED_COUNT)
+    }
+
+    /// Number of RGB LEDs, each consisting of three OUT channels.
+    pub const fn rgb_count(self) -> u8 {
+        variant_const!(self, RGB_COUNT)
+    }
+
+    /// I2C address for [Address::Address0], on which the other addresses are based.
+    pub const fn address_base(self) -> u8 {
+        variant_const!(self, I2C_ADDRESS_BASE)
+    }
+
+    /// I2C address for [Address::Broadcast].
+    pub const fn broadcast_address(self) -> u8 {
+        variant_const!(self, I2C_ADDRESS_BROADCAST)
+    }
+}
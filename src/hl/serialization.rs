@@ -0,0 +1,23 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::MaxCurrentOption;
+
+/// Mirror of the generated [MaxCurrentOption], serialized by variant name.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "MaxCurrentOption")]
+enum MaxCurrentOptionDef {
+    Current25MA5,
+    Current35MA,
+}
+
+impl Serialize for MaxCurrentOption {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MaxCurrentOptionDef::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MaxCurrentOption {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        MaxCurrentOptionDef::deserialize(deserializer)
+    }
+}
@@ -0,0 +1,57 @@
+use embedded_hal_async::i2c::I2c;
+use smart_leds_trait::{SmartLedsWriteAsync, RGB8};
+
+use crate::{
+    hl::{MAX_LED_COUNT, MAX_RGB_COUNT},
+    marker, Driver, Error, LP50xx, Rgb,
+};
+
+impl<VARIANT: LP50xx, T: I2c, STATE: marker::Marker, const N: usize> Driver<VARIANT, T, STATE, N> {
+    /// Read the colors of all RGB LEDs.
+    ///
+    /// Yields exactly `RGB_COUNT` colors, as written to the device (thus including color correction).
+    ///
+    /// Will return the [Error::BroadcastRead] if the driver was constructed with [crate::Address::Broadcast].
+    pub async fn read_frame_rgb8(
+        &mut self,
+    ) -> Result<impl ExactSizeIterator<Item = RGB8>, Error<T::Error>> {
+        let mut buf = [0u8; MAX_LED_COUNT];
+        self.read_block(
+            VARIANT::OUT_START_ADDRESS,
+            &mut buf[..VARIANT::LED_COUNT as usize],
+        )
+        .await?;
+
+        Ok((0..VARIANT::RGB_COUNT as usize)
+            .map(move |i| RGB8::new(buf[i * 3], buf[i * 3 + 1], buf[i * 3 + 2])))
+    }
+}
+
+/// Writes the colors of the RGB LEDs, starting at the first RGB LED, using [Driver::set_all_rgb].
+///
+/// If fewer colors than RGB LEDs are yielded, the remaining RGB LEDs are left untouched.
+/// Will return the [Error::Index] without writing anything if more colors than RGB LEDs are yielded.
+impl<VARIANT: LP50xx, T: I2c, const N: usize> SmartLedsWriteAsync
+    for Driver<VARIANT, T, marker::Normal, N>
+{
+    type Error = Error<T::Error>;
+    type Color = RGB8;
+
+    async fn write<C, I>(&mut self, iterator: C) -> Result<(), Self::Error>
+    where
+        C: IntoIterator<Item = I>,
+        I: Into<Self::Color>,
+    {
+        let mut frame: heapless::Vec<Rgb, MAX_RGB_COUNT> = heapless::Vec::new();
+        for color in iterator {
+            let color: RGB8 = color.into();
+            if frame.len() == VARIANT::RGB_COUNT as usize {
+                return Err(Error::Index);
+            }
+            // Note: capacity is at least RGB_COUNT.
+            let _ = frame.push(Rgb([color.r, color.g, color.b]));
+        }
+
+        self.set_all_rgb(&frame).await
+    }
+}
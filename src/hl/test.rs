@@ -1,10 +1,11 @@
 use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
 
 use crate::{
-    hl::{self, Address, Config, LP50xx},
+    hl::{self, Address, Config, LP50xx, Variant},
     ll,
 };
 
+#[allow(clippy::unusual_byte_groupings)]
 const ADDRESS: u8 = 0b0110_001;
 
 fn regw(register: u8, values: &[u8]) -> Transaction {
@@ -13,6 +14,15 @@ fn regw(register: u8, values: &[u8]) -> Transaction {
     Transaction::write(ADDRESS, expected)
 }
 
+/// Consecutive register writes, split into transactions like the driver does.
+fn regw_block(register: u8, values: &[u8]) -> Vec<Transaction> {
+    values
+        .chunks(12)
+        .enumerate()
+        .map(|(i, chunk)| regw(register + (i * 12) as u8, chunk))
+        .collect()
+}
+
 fn regr(register: u8, values: &[u8]) -> Transaction {
     Transaction::write_read(ADDRESS, vec![register], Vec::from(values))
 }
@@ -39,6 +49,7 @@ async fn lp5030() {
         power_save: true,
         pwm_dithering: false,
         max_current: ll::MaxCurrentOption::Current35MA,
+        supply_voltage_mv: None,
     })
     .await
     .unwrap();
@@ -50,3 +61,2187 @@ async fn lp5030() {
 
     i2c.done();
 }
+
+#[test]
+fn variant() {
+    fn check<V: LP50xx>(variant: Variant) {
+        assert_eq!(variant.led_count(), V::LED_COUNT);
+        assert_eq!(variant.rgb_count(), V::RGB_COUNT);
+        assert_eq!(variant.address_base(), V::I2C_ADDRESS_BASE);
+        assert_eq!(variant.broadcast_address(), V::I2C_ADDRESS_BROADCAST);
+    }
+
+    check::<hl::LP5009>(Variant::Lp5009);
+    check::<hl::LP5012>(Variant::Lp5012);
+    check::<hl::LP5018>(Variant::Lp5018);
+    check::<hl::LP5024>(Variant::Lp5024);
+    check::<hl::LP5030>(Variant::Lp5030);
+    check::<hl::LP5036>(Variant::Lp5036);
+
+    assert_eq!(Variant::ALL.len(), 6);
+}
+
+#[async_std::test]
+async fn bank_atomic() {
+    let expectations = [
+        regr(0x01, &[0x3C]),
+        regw(0x01, &[0x3D]),
+        regw(0x02, &[0x01, 0x08, 0x80, 0x01, 0x02, 0x03]),
+        regr(0x01, &[0x3D]),
+        regw(0x01, &[0x3C]),
+    ];
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    hl.set_bank_atomic(0b1000_0000_0001, (0x01, 0x02, 0x03), 0x80)
+        .await
+        .unwrap();
+    assert_eq!(
+        hl.set_bank_atomic(1 << 12, (0x01, 0x02, 0x03), 0x80).await,
+        Err(hl::Error::Index)
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn bank_atomic_failed_write() {
+    use embedded_hal::i2c::ErrorKind;
+
+    let expectations = [
+        regr(0x01, &[0x3C]),
+        regw(0x01, &[0x3D]),
+        regw(0x02, &[0x01, 0x00, 0x80, 0x01, 0x02, 0x03]).with_error(ErrorKind::Other),
+        regr(0x01, &[0x3D]),
+        regw(0x01, &[0x3C]),
+    ];
+
+    let mut i2c = Mock::new(&expectations);
+
+    // LED_Global_Off is cleared again, and the error of the failed write is returned.
+    let mut hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    assert_eq!(
+        hl.set_bank_atomic(0b1, (0x01, 0x02, 0x03), 0x80).await,
+        Err(hl::Error::Interface(ErrorKind::Other))
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn bank_atomic_broadcast() {
+    let mut i2c = Mock::new(&[]);
+
+    let mut hl = hl::LP5036::new(&mut i2c, Address::Broadcast);
+    assert_eq!(
+        hl.set_bank_atomic(0b1, (0x01, 0x02, 0x03), 0x80).await,
+        Err(hl::Error::BroadcastRead)
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn bank_atomic_single_config() {
+    let expectations = [
+        regr(0x01, &[0x3C]),
+        regw(0x01, &[0x3D]),
+        regw(0x02, &[0x05, 0xFF, 0x10, 0x20, 0x30]),
+        regr(0x01, &[0x3D]),
+        regw(0x01, &[0x3C]),
+    ];
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5012::new(&mut i2c, Address::Address1);
+    hl.set_bank_atomic(0b101, (0x10, 0x20, 0x30), 0xFF)
+        .await
+        .unwrap();
+
+    i2c.done();
+}
+
+/// The core API must remain available when building without the `animations` feature.
+#[cfg(not(feature = "animations"))]
+#[async_std::test]
+async fn without_animations() {
+    let expectations = [regw(0x00, &[0x40]), regw(0x2F, &[0x01, 0x02, 0x03])];
+
+    let mut i2c = Mock::new(&expectations);
+
+    let hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    let mut hl = hl.enable().await.unwrap();
+    hl.set_rgb(9, hl::Rgb([0x01, 0x02, 0x03])).await.unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn log_scale() {
+    assert_eq!(hl::linear_to_log(0), 0);
+    assert_eq!(hl::linear_to_log(1), 19);
+    assert_eq!(hl::linear_to_log(15), 127);
+    assert_eq!(hl::linear_to_log(76), 200);
+    assert_eq!(hl::linear_to_log(255), 255);
+
+    assert_eq!(hl::log_to_linear(0), 0);
+    assert_eq!(hl::log_to_linear(128), 15);
+    assert_eq!(hl::log_to_linear(200), 76);
+    assert_eq!(hl::log_to_linear(255), 255);
+
+    // The smallest code reaching at least the requested brightness is picked.
+    for value in 0..=255 {
+        assert!(hl::log_to_linear(hl::linear_to_log(value)) >= value);
+    }
+}
+
+#[async_std::test]
+async fn correction_lut() {
+    let expectations = [
+        regw(0x2F, &[0xFE, 0x02, 0x01]),
+        regw(0x29, &[0x00]),
+        regw(0x2F, &[0x01, 0x02, 0x03]),
+    ];
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut lut = [hl::Rgb([0; 3]); 256];
+    for (i, entry) in lut.iter_mut().enumerate() {
+        let i = i as u8;
+        *entry = hl::Rgb([255 - i, i, i / 2]);
+    }
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_correction_lut(Some(Box::leak(Box::new(lut))));
+    hl.set_rgb(9, (0x01, 0x02, 0x03)).await.unwrap();
+    hl.set_channel(21, 0xFF).await.unwrap();
+
+    hl.set_correction_lut(None);
+    hl.set_rgb(9, (0x01, 0x02, 0x03)).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn progress() {
+    const ON: [u8; 3] = [0xFF, 0x00, 0x00];
+    const OFF: [u8; 3] = [0x00, 0x00, 0x10];
+
+    let empty = OFF.repeat(10);
+    let mut half = ON.repeat(5);
+    half.extend_from_slice(&[0x05, 0x00, 0x10]);
+    half.extend(OFF.repeat(4));
+    let full = ON.repeat(10);
+
+    let mut expectations = regw_block(0x14, &empty);
+    expectations.extend(regw_block(0x14, &half));
+    expectations.extend(regw_block(0x14, &full));
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_progress(0, ON, OFF).await.unwrap();
+    hl.set_progress(128, ON, OFF).await.unwrap();
+    hl.set_progress(255, ON, OFF).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn all_white() {
+    let mut expectations = vec![regw(0x08, &[0x40; 10])];
+    expectations.extend(regw_block(0x14, &[0xFF; 30]));
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.all_white(0x40).await.unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn rgb_ordering() {
+    let mut colors = vec![
+        hl::Rgb([0x10, 0x00, 0x00]),
+        hl::Rgb([0x00, 0x00, 0xFF]),
+        hl::Rgb([0x00, 0x10, 0x00]),
+        hl::Rgb([0x10, 0x00, 0x01]),
+        hl::Rgb([0x00, 0x00, 0x00]),
+    ];
+    colors.sort();
+
+    assert_eq!(
+        colors,
+        [
+            hl::Rgb([0x00, 0x00, 0x00]),
+            hl::Rgb([0x00, 0x00, 0xFF]),
+            hl::Rgb([0x00, 0x10, 0x00]),
+            hl::Rgb([0x10, 0x00, 0x00]),
+            hl::Rgb([0x10, 0x00, 0x01]),
+        ]
+    );
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn fade_job() {
+    use core::task::Poll;
+
+    let expectations = [
+        regw(0x2F, &[63; 3]),
+        regw(0x2F, &[127; 3]),
+        regw(0x2F, &[191; 3]),
+        regw(0x2F, &[255; 3]),
+    ];
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    let mut job = hl::FadeJob::new(9, [0x00; 3], [0xFF; 3], 4);
+
+    let mut polls = 0;
+    while job.poll(&mut hl).await.unwrap().is_pending() {
+        polls += 1;
+    }
+    assert_eq!(polls, 3);
+    assert!(job.is_done());
+    assert_eq!(job.poll(&mut hl).await.unwrap(), Poll::Ready(()));
+
+    i2c.done();
+}
+
+#[test]
+fn register_blocks_do_not_overlap() {
+    fn check<V: LP50xx>(name: &str) {
+        let brightness_end = V::LED_START_ADDRESS + V::RGB_COUNT;
+        assert!(
+            brightness_end <= V::OUT_START_ADDRESS,
+            "{name}: brightness block [{:#04x}, {brightness_end:#04x}) overlaps color block starting at {:#04x}",
+            V::LED_START_ADDRESS,
+            V::OUT_START_ADDRESS,
+        );
+    }
+
+    check::<hl::LP5009>("LP5009");
+    check::<hl::LP5012>("LP5012");
+    check::<hl::LP5018>("LP5018");
+    check::<hl::LP5024>("LP5024");
+    check::<hl::LP5030>("LP5030");
+    check::<hl::LP5036>("LP5036");
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn play_stream() {
+    use embedded_hal_mock::eh1::delay::{self, CheckedDelay};
+
+    let frames: [&[u8]; 3] = [&[0x01; 9], &[0x02; 9], &[0x03; 8]];
+
+    let expectations = [regw(0x0B, &[0x01; 9]), regw(0x0B, &[0x02; 9])];
+    let mut i2c = Mock::new(&expectations);
+    let mut delay = CheckedDelay::new(&[
+        delay::Transaction::delay_us(1000),
+        delay::Transaction::delay_us(1000),
+    ]);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.play_stream(frames[..2].iter().copied(), &mut delay, 1000)
+        .await
+        .unwrap();
+    delay.done();
+
+    // Playback stops at the frame with an incorrect length.
+    let mut delay = CheckedDelay::new(&[]);
+    assert_eq!(
+        hl.play_stream(frames[2..].iter().copied(), &mut delay, 1000)
+            .await,
+        Err(hl::Error::Index)
+    );
+
+    i2c.done();
+    delay.done();
+}
+
+#[async_std::test]
+async fn max_i2c_transfer() {
+    let expectations = [
+        regw(0x07, &[0x40; 3]),
+        regw(0x0B, &[0xFF; 4]),
+        regw(0x0F, &[0xFF; 4]),
+        regw(0x13, &[0xFF; 1]),
+        regw(0x0B, &[0x01, 0x02, 0x03]),
+    ];
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.set_max_i2c_transfer(5);
+    hl.all_white(0x40).await.unwrap();
+    hl.set_rgb(0, (0x01, 0x02, 0x03)).await.unwrap();
+
+    i2c.done();
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn scheduler() {
+    struct Solid(hl::Rgb);
+
+    impl hl::Animation for Solid {
+        fn render(&mut self, frame: &mut [hl::Rgb]) {
+            frame.fill(self.0);
+        }
+    }
+
+    struct Counter(u8);
+
+    impl hl::Animation for Counter {
+        fn render(&mut self, frame: &mut [hl::Rgb]) {
+            for (i, value) in frame.iter_mut().enumerate() {
+                *value = hl::Rgb([0, 0, self.0 + i as u8]);
+            }
+            self.0 += 1;
+        }
+    }
+
+    let mut first = [0xFF, 0x00, 0x00].repeat(2);
+    first.extend([0x00; 9]);
+    first.extend([0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x02]);
+    first.extend([0x00; 6]);
+
+    let mut second = first.clone();
+    second[17] = 0x01;
+    second[20] = 0x02;
+    second[23] = 0x03;
+
+    let mut expectations = regw_block(0x14, &first);
+    expectations.extend(regw_block(0x14, &second));
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut solid = Solid(hl::Rgb([0xFF, 0x00, 0x00]));
+    let mut counter = Counter(0);
+    let mut overlapping = Solid(hl::Rgb([0x00, 0xFF, 0x00]));
+
+    let mut scheduler = hl::Scheduler::<2>::new();
+    scheduler.add(0..2, &mut solid).unwrap();
+    scheduler.add(5..8, &mut counter).unwrap();
+    assert_eq!(
+        scheduler.add(1..3, &mut overlapping),
+        Err(hl::ScheduleError::Overlap)
+    );
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    scheduler.step(&mut hl).await.unwrap();
+    scheduler.step(&mut hl).await.unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn rgb_gray() {
+    assert_eq!(hl::Rgb::gray(128), hl::Rgb([128, 128, 128]));
+    assert_eq!(hl::Rgb::gray(0), hl::Rgb([0, 0, 0]));
+}
+
+#[test]
+fn frame_time() {
+    let mut i2c = Mock::new(&[]);
+    let lp5009 = hl::LP5009::new(&mut i2c, Address::Address1);
+    // One transaction of 20 bits overhead and 9 bytes.
+    assert_eq!(lp5009.frame_time_us(400_000), 253);
+
+    let mut i2c2 = Mock::new(&[]);
+    let lp5036 = hl::LP5036::new(&mut i2c2, Address::Address1);
+    // Three transactions of 20 bits overhead and 36 bytes.
+    assert_eq!(lp5036.frame_time_us(400_000), 960);
+    assert_eq!(lp5036.frame_time_us(100_000), 3840);
+
+    i2c.done();
+    i2c2.done();
+}
+
+#[cfg(feature = "std")]
+#[async_std::test]
+async fn recording_interface() {
+    use crate::recording::{Record, RecordingInterface};
+
+    let mut i2c = RecordingInterface::new();
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_rgb(9, (0x01, 0x02, 0x03)).await.unwrap();
+
+    assert_eq!(
+        i2c.log(),
+        [Record::Write {
+            address: ADDRESS,
+            bytes: vec![0x2F, 0x01, 0x02, 0x03]
+        }]
+    );
+    assert_eq!(i2c.take_log().len(), 1);
+    assert!(i2c.log().is_empty());
+}
+
+#[test]
+fn rgb_blend() {
+    let base = hl::Rgb([200, 100, 255]);
+    let layer = hl::Rgb([128, 255, 0]);
+
+    assert_eq!(base.multiply(layer), hl::Rgb([100, 100, 0]));
+    assert_eq!(base.multiply(hl::Rgb::gray(255)), base);
+    assert_eq!(base.multiply(hl::Rgb::gray(0)), hl::Rgb::gray(0));
+
+    assert_eq!(base.screen(layer), hl::Rgb([228, 255, 255]));
+    assert_eq!(base.screen(hl::Rgb::gray(0)), base);
+    assert_eq!(base.screen(hl::Rgb::gray(255)), hl::Rgb::gray(255));
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn hue_sweep() {
+    use embedded_hal_mock::eh1::delay::{self, CheckedDelay};
+
+    let frames: [[u8; 3]; 3] = [[255, 0, 0], [1, 255, 0], [0, 3, 255]];
+
+    let expectations: Vec<_> = frames
+        .iter()
+        .cycle()
+        .take(6)
+        .map(|color| regw(0x0B, &color.repeat(3)))
+        .collect();
+    let mut i2c = Mock::new(&expectations);
+    let mut delay = CheckedDelay::new(&vec![delay::Transaction::delay_us(500); 6]);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.run_hue_sweep((255, 255), 120, 500, 2, &mut delay)
+        .await
+        .unwrap();
+
+    i2c.done();
+    delay.done();
+}
+
+#[async_std::test]
+async fn muxed_interface() {
+    use crate::mux::{MuxError, MuxedInterface};
+    use core::cell::Cell;
+
+    let expectations = [regw(0x00, &[0x40]), regw(0x2F, &[0x01, 0x02, 0x03])];
+
+    let mut i2c = Mock::new(&expectations);
+    let selects = Cell::new(0);
+    let fail = Cell::new(false);
+    let muxed = MuxedInterface::new(&mut i2c, || {
+        if fail.get() {
+            return Err("select failed");
+        }
+        selects.set(selects.get() + 1);
+        Ok(())
+    });
+
+    let hl = hl::LP5030::new(muxed, Address::Address1);
+    let mut hl = hl.enable().await.unwrap();
+    assert_eq!(selects.get(), 1);
+    hl.set_rgb(9, (0x01, 0x02, 0x03)).await.unwrap();
+    assert_eq!(selects.get(), 2);
+
+    // Failing to select aborts the transaction.
+    fail.set(true);
+    assert_eq!(
+        hl.set_rgb(9, (0x01, 0x02, 0x03)).await,
+        Err(hl::Error::Interface(MuxError::Select("select failed")))
+    );
+
+    i2c.done();
+}
+
+#[test]
+fn rgb_with_value() {
+    use super::color::rgb_to_hsv;
+
+    for color in [
+        hl::Rgb([128, 64, 0]),
+        hl::Rgb([10, 20, 40]),
+        hl::Rgb([200, 0, 100]),
+        hl::Rgb::gray(50),
+    ] {
+        assert_eq!(color.with_value(0), hl::Rgb::gray(0));
+
+        let (h, s, _) = rgb_to_hsv(color);
+        let (bright_h, bright_s, bright_v) = rgb_to_hsv(color.with_value(255));
+        assert!(
+            h.abs_diff(bright_h) <= 1,
+            "{color:?}: hue {h} became {bright_h}"
+        );
+        assert!(
+            s.abs_diff(bright_s) <= 1,
+            "{color:?}: saturation {s} became {bright_s}"
+        );
+        assert_eq!(bright_v, 255);
+    }
+
+    assert_eq!(hl::Rgb([128, 0, 0]).with_value(255), hl::Rgb([255, 0, 0]));
+}
+
+#[async_std::test]
+async fn buffer_size() {
+    let mut expectations = vec![regw(0x08, &[0x40; 12])];
+    expectations.extend(regw_block(0x14, &[0xFF; 36]));
+    expectations.push(regw(0x08, &[0x40; 12]));
+    expectations.push(regw(0x14, &[0xFF; 36]));
+    expectations.extend([0x08, 0x0A, 0x0C, 0x0E, 0x10, 0x12].map(|r| regw(r, &[0x40; 2])));
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5036::new_with_buffer::<_, 13>(&mut i2c, Address::Address1);
+    hl.all_white(0x40).await.unwrap();
+
+    let mut hl = hl::LP5036::new_with_buffer::<_, 37>(&mut i2c, Address::Address1);
+    hl.all_white(0x40).await.unwrap();
+
+    let mut hl = hl::LP5036::new_with_buffer::<_, 3>(&mut i2c, Address::Address1);
+    hl.set_all_brightness(0x40).await.unwrap();
+
+    i2c.done();
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn blink_binary() {
+    use embedded_hal_mock::eh1::delay::{self, CheckedDelay};
+
+    const ON: [u8; 3] = [0x00, 0xFF, 0x00];
+    const OFF: [u8; 3] = [0xFF, 0x00, 0x00];
+    const DARK: [u8; 3] = [0x00; 3];
+
+    let timing = hl::BlinkTiming {
+        marker_ms: 100,
+        bit_ms: 20,
+        gap_ms: 10,
+    };
+
+    let pulses = [
+        (ON, 100),
+        (ON, 20),
+        (OFF, 20),
+        (ON, 20),
+        (ON, 20),
+        (OFF, 20),
+        (OFF, 20),
+        (OFF, 20),
+        (ON, 20),
+        (ON, 100),
+    ];
+
+    let mut expectations = vec![];
+    let mut delays = vec![];
+    for (color, duration_ms) in pulses {
+        expectations.extend([regw(0x0E, &color), regw(0x0E, &DARK)]);
+        delays.extend([
+            delay::Transaction::delay_ms(duration_ms),
+            delay::Transaction::delay_ms(10),
+        ]);
+    }
+
+    let mut i2c = Mock::new(&expectations);
+    let mut delay = CheckedDelay::new(&delays);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.blink_binary(1, 0b1011_0001, ON, OFF, timing, &mut delay)
+        .await
+        .unwrap();
+
+    i2c.done();
+    delay.done();
+}
+
+#[cfg(feature = "smart-leds")]
+#[async_std::test]
+async fn read_frame_rgb8() {
+    use smart_leds_trait::RGB8;
+
+    let expectations = [regr(
+        0x0B,
+        &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09],
+    )];
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    let frame = hl.read_frame_rgb8().await.unwrap();
+    assert_eq!(frame.len(), 3);
+    assert_eq!(
+        frame.collect::<Vec<_>>(),
+        [
+            RGB8::new(0x01, 0x02, 0x03),
+            RGB8::new(0x04, 0x05, 0x06),
+            RGB8::new(0x07, 0x08, 0x09)
+        ]
+    );
+
+    i2c.done();
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn notify() {
+    use embedded_hal_mock::eh1::delay::{self, CheckedDelay};
+
+    let expectations = [[0x00, 0x00, 0xFF], [0x00; 3]].repeat(3);
+    let expectations: Vec<_> = expectations.iter().map(|c| regw(0x11, c)).collect();
+
+    let mut i2c = Mock::new(&expectations);
+    let mut delay = CheckedDelay::new(&vec![delay::Transaction::delay_ms(100); 6]);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.notify(2, (0x00, 0x00, 0xFF), 3, &mut delay)
+        .await
+        .unwrap();
+    assert_eq!(
+        hl.notify(3, (0x00, 0x00, 0xFF), 3, &mut delay).await,
+        Err(hl::Error::Index)
+    );
+
+    i2c.done();
+    delay.done();
+}
+
+#[test]
+fn config_presets() {
+    let config = Config::high_efficiency();
+    assert!(config.log_scale);
+    assert!(config.power_save);
+    assert!(config.pwm_dithering);
+    assert_eq!(config.max_current, ll::MaxCurrentOption::Current25MA5);
+
+    let config = Config::max_brightness();
+    assert!(config.log_scale);
+    assert!(!config.power_save);
+    assert!(config.pwm_dithering);
+    assert_eq!(config.max_current, ll::MaxCurrentOption::Current35MA);
+}
+
+#[async_std::test]
+async fn set_rgb_channels() {
+    let expectations = [
+        regw(0x2F, &[0x01, 0x02, 0x03]),
+        regw(0x2F, &[0x01, 0x02, 0x03]),
+    ];
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_rgb_channels(9, 0x01, 0x02, 0x03).await.unwrap();
+    hl.set_rgb(9, hl::Rgb([0x01, 0x02, 0x03])).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn diagnose_channel() {
+    let expectations = [
+        // Tracking register.
+        regr(0x18, &[0x12]),
+        regw(0x18, &[0x55]),
+        regr(0x18, &[0x55]),
+        regw(0x18, &[0xAA]),
+        regr(0x18, &[0xAA]),
+        regw(0x18, &[0x12]),
+        // Stuck register.
+        regr(0x19, &[0x00]),
+        regw(0x19, &[0x55]),
+        regr(0x19, &[0x00]),
+        regw(0x19, &[0xAA]),
+        regr(0x19, &[0x00]),
+        regw(0x19, &[0x00]),
+    ];
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    assert!(hl.diagnose_channel(4).await.unwrap());
+    assert!(!hl.diagnose_channel(5).await.unwrap());
+    assert!(matches!(
+        hl.diagnose_channel(30).await,
+        Err(hl::Error::Index)
+    ));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn max_rgb_per_write() {
+    async fn check<V: LP50xx>() {
+        let address = Address::Address1.i2c_address::<V>();
+        let colors: Vec<hl::Rgb> = (0..V::RGB_COUNT).map(hl::Rgb::gray).collect();
+        let data: Vec<u8> = colors.iter().flat_map(|c| c.0).collect();
+
+        let chunk_size = V::MAX_RGB_PER_WRITE as usize * 3;
+        let expectations: Vec<Transaction> = data
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut expected = vec![V::OUT_START_ADDRESS + (i * chunk_size) as u8];
+                expected.extend_from_slice(chunk);
+                Transaction::write(address, expected)
+            })
+            .collect();
+
+        let mut i2c = Mock::new(&expectations);
+
+        let mut hl = V::new(&mut i2c, Address::Address1);
+        hl.write_rgbs(0, &colors).await.unwrap();
+
+        i2c.done();
+    }
+
+    assert_eq!(hl::LP5036::MAX_RGB_PER_WRITE, 4);
+
+    check::<hl::LP5009>().await;
+    check::<hl::LP5012>().await;
+    check::<hl::LP5018>().await;
+    check::<hl::LP5024>().await;
+    check::<hl::LP5030>().await;
+    check::<hl::LP5036>().await;
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn fade_channels() {
+    use embedded_hal_mock::eh1::delay::{self, CheckedDelay};
+
+    let expectations = [
+        regw(0x2F, &[0x00, 0xFF, 0x10]),
+        regw(0x2F, &[0x1C, 0xE3, 0x1E]),
+        regw(0x2F, &[0x71, 0x8E, 0x49]),
+        regw(0x2F, &[0xFF, 0x00, 0x90]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut delay = CheckedDelay::new(&vec![delay::Transaction::delay_us(1000); 4]);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.fade_channels(
+        9,
+        [0x00, 0xFF, 0x10],
+        [0xFF, 0x00, 0x90],
+        4,
+        hl::Easing::EaseIn,
+        &mut delay,
+        1000,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(
+        hl.fade_channels(
+            10,
+            [0x00; 3],
+            [0xFF; 3],
+            4,
+            hl::Easing::Linear,
+            &mut delay,
+            1000
+        )
+        .await,
+        Err(hl::Error::Index)
+    ));
+
+    i2c.done();
+    delay.done();
+}
+
+#[cfg(feature = "animations")]
+#[test]
+fn easing() {
+    use hl::Easing;
+
+    for easing in [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ] {
+        assert_eq!(easing.apply(0), 0);
+        assert_eq!(easing.apply(255), 255);
+    }
+    assert_eq!(Easing::Linear.apply(85), 85);
+    assert!(Easing::EaseIn.apply(85) < 85);
+    assert!(Easing::EaseOut.apply(85) > 85);
+    assert!(Easing::EaseInOut.apply(64) < 64);
+    assert!(Easing::EaseInOut.apply(191) > 191);
+}
+
+#[test]
+fn color_scale() {
+    const COLD: hl::Rgb = hl::Rgb([0x00, 0x00, 0xFF]);
+    const HOT: hl::Rgb = hl::Rgb([0xFF, 0x00, 0x00]);
+
+    assert_eq!(hl::color_scale(0, COLD, HOT), COLD);
+    assert_eq!(hl::color_scale(128, COLD, HOT), hl::Rgb([0x80, 0x00, 0x7F]));
+    assert_eq!(hl::color_scale(255, COLD, HOT), HOT);
+}
+
+#[test]
+fn chunks_needed() {
+    let mut i2c = Mock::new(&[]);
+    let mut hl = hl::LP5036::new_with_buffer::<_, 37>(&mut i2c, Address::Address1);
+    assert_eq!(hl.chunks_needed(0), 0);
+    assert_eq!(hl.chunks_needed(1), 1);
+    assert_eq!(hl.chunks_needed(36), 1);
+    assert_eq!(hl.chunks_needed(37), 2);
+
+    hl.set_max_i2c_transfer(13);
+    assert_eq!(hl.chunks_needed(12), 1);
+    assert_eq!(hl.chunks_needed(13), 2);
+    assert_eq!(hl.chunks_needed(24), 2);
+    assert_eq!(hl.chunks_needed(25), 3);
+
+    i2c.done();
+}
+
+#[cfg(feature = "animations")]
+#[test]
+fn marching_ants() {
+    use hl::Animation;
+
+    const H: hl::Rgb = hl::Rgb([0xFF, 0xFF, 0x00]);
+    const B: hl::Rgb = hl::Rgb([0x00, 0x00, 0x10]);
+    const X: hl::Rgb = hl::Rgb([0x01, 0x02, 0x03]);
+
+    let mut ants = hl::MarchingAnts::new(H, B, 2, 2);
+    let mut frame = [X; 8];
+
+    ants.render(&mut frame[1..7]);
+    assert_eq!(frame, [X, H, H, B, B, H, H, X]);
+    ants.render(&mut frame[1..7]);
+    assert_eq!(frame, [X, H, H, B, B, H, H, X]);
+    ants.render(&mut frame[1..7]);
+    assert_eq!(frame, [X, B, H, H, B, B, H, X]);
+    ants.render(&mut frame[1..7]);
+    ants.render(&mut frame[1..7]);
+    assert_eq!(frame, [X, B, B, H, H, B, B, X]);
+
+    ants.shift();
+    ants.shift();
+    assert_eq!(ants.color_at(0), H);
+}
+
+#[async_std::test]
+async fn configured() {
+    use embedded_hal_mock::eh1::delay::{self, CheckedDelay};
+
+    let expectations = [
+        regw(0x38, &[0xFF]),
+        regw(0x00, &[0x40]),
+        regr(0x01, &[0x3C]),
+        regw(0x01, &[0x2E]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut delay = CheckedDelay::new(&[delay::Transaction::delay_us(500)]);
+
+    hl::LP5030::configured(
+        &mut i2c,
+        Address::Address1,
+        &Config::max_brightness(),
+        &mut delay,
+    )
+    .await
+    .unwrap();
+
+    i2c.done();
+    delay.done();
+}
+
+#[test]
+fn reset_address() {
+    assert_eq!(hl::LP5009::RESET_ADDRESS, 0x17);
+    assert_eq!(hl::LP5012::RESET_ADDRESS, 0x17);
+    assert_eq!(hl::LP5018::RESET_ADDRESS, 0x27);
+    assert_eq!(hl::LP5024::RESET_ADDRESS, 0x27);
+    assert_eq!(hl::LP5030::RESET_ADDRESS, 0x38);
+    assert_eq!(hl::LP5036::RESET_ADDRESS, 0x38);
+}
+
+#[test]
+fn rgb_from_ansi256() {
+    assert_eq!(hl::Rgb::from_ansi256(1), hl::Rgb([0x80, 0x00, 0x00]));
+    assert_eq!(hl::Rgb::from_ansi256(12), hl::Rgb([0x00, 0x00, 0xFF]));
+    assert_eq!(hl::Rgb::from_ansi256(16), hl::Rgb([0, 0, 0]));
+    assert_eq!(hl::Rgb::from_ansi256(208), hl::Rgb([255, 135, 0]));
+    assert_eq!(hl::Rgb::from_ansi256(231), hl::Rgb([255, 255, 255]));
+    assert_eq!(hl::Rgb::from_ansi256(232), hl::Rgb::gray(8));
+    assert_eq!(hl::Rgb::from_ansi256(244), hl::Rgb::gray(128));
+    assert_eq!(hl::Rgb::from_ansi256(255), hl::Rgb::gray(238));
+}
+
+#[cfg(all(debug_assertions, feature = "log"))]
+#[async_std::test]
+async fn brightness_without_color_warning() {
+    use std::sync::Mutex;
+
+    struct Capture(Mutex<Vec<String>>);
+
+    impl log::Log for Capture {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Warn
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+    log::set_logger(&CAPTURE).unwrap();
+    log::set_max_level(log::LevelFilter::Warn);
+
+    // The colors are tracked in software, the warning never causes extra transactions.
+    let expectations = [
+        regw(0x0F, &[0x80]),
+        regw(0x29, &[0x00, 0x10, 0x00]),
+        regw(0x0F, &[0x80]),
+        regw(0x11, &[0x00]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let warnings = |rgb_i: u8| {
+        let needle = format!("RGB LED {rgb_i} ");
+        CAPTURE
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|message| message.contains(&needle))
+            .count()
+    };
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+
+    // Black after construction, as after a reset of the device.
+    hl.set_rgb_brightness(7, 0x80).await.unwrap();
+    assert_eq!(warnings(7), 1);
+
+    hl.set_rgb(7, (0x00, 0x10, 0x00)).await.unwrap();
+    hl.set_rgb_brightness(7, 0x80).await.unwrap();
+    assert_eq!(warnings(7), 1);
+
+    // A brightness of zero keeps the RGB LED dark anyway.
+    hl.set_rgb_brightness(9, 0x00).await.unwrap();
+    assert_eq!(warnings(9), 0);
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn config_report() {
+    let expectations = [regr(0x00, &[0x40]), regr(0x01, &[0x3A])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    assert_eq!(
+        hl.config_report().await.unwrap(),
+        hl::ConfigReport {
+            enabled: true,
+            log_scale: true,
+            power_save: true,
+            pwm_dithering: false,
+            max_current: ll::MaxCurrentOption::Current35MA,
+            auto_increment: true,
+        }
+    );
+
+    i2c.done();
+}
+
+#[test]
+fn thermal_limit_brightness() {
+    use ll::MaxCurrentOption::{Current25MA5, Current35MA};
+
+    // 2V over each of the 24 current sinks: 1.68W at 35mA, rising 67.2°C at 40°C/W.
+    let design = hl::ThermalDesign {
+        theta_ja_c_per_w: 40,
+        led_supply_mv: 5000,
+        led_forward_mv: 3000,
+        channels: 24,
+    };
+
+    assert_eq!(hl::thermal_limit_brightness(25, Current35MA, &design), 255);
+    assert_eq!(hl::thermal_limit_brightness(85, Current35MA, &design), 246);
+    assert_eq!(hl::thermal_limit_brightness(120, Current35MA, &design), 113);
+    assert_eq!(hl::thermal_limit_brightness(150, Current35MA, &design), 0);
+    // 1.224W at 25.5mA, rising 49°C: no derating needed at 85°C.
+    assert_eq!(hl::thermal_limit_brightness(85, Current25MA5, &design), 255);
+    assert_eq!(
+        hl::thermal_limit_brightness(120, Current25MA5, &design),
+        156
+    );
+    assert_eq!(
+        hl::thermal_limit_brightness(i16::MAX, Current25MA5, &design),
+        0
+    );
+    assert_eq!(
+        hl::thermal_limit_brightness(i16::MIN, Current35MA, &design),
+        255
+    );
+
+    // A higher thermal resistance derates within the operating range at the default current.
+    let design = hl::ThermalDesign {
+        theta_ja_c_per_w: 60,
+        ..design
+    };
+    assert_eq!(hl::thermal_limit_brightness(85, Current25MA5, &design), 225);
+
+    // Without dissipation no derating is needed, up to the junction limit.
+    let design = hl::ThermalDesign {
+        led_forward_mv: 5000,
+        ..design
+    };
+    assert_eq!(hl::thermal_limit_brightness(149, Current35MA, &design), 255);
+    assert_eq!(hl::thermal_limit_brightness(150, Current35MA, &design), 0);
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn fade_set() {
+    let expectations = [
+        regw(0x0B, &[0xFF; 3]),
+        regw(0x0E, &[0x7F; 3]),
+        regw(0x0E, &[0xFF; 3]),
+        regw(0x11, &[0xFF; 3]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    let mut fades = hl::FadeSet::<2>::new();
+    fades
+        .add(hl::FadeJob::new(0, [0x00; 3], [0xFF; 3], 1))
+        .unwrap();
+    fades
+        .add(hl::FadeJob::new(1, [0x00; 3], [0xFF; 3], 2))
+        .unwrap();
+    assert!(fades
+        .add(hl::FadeJob::new(2, [0x00; 3], [0xFF; 3], 1))
+        .is_err());
+
+    assert_eq!(fades.tick(&mut hl).await.unwrap(), 0b001);
+
+    // Chain the next fade once the first one completed.
+    fades
+        .add(hl::FadeJob::new(2, [0x00; 3], [0xFF; 3], 1))
+        .unwrap();
+    assert_eq!(fades.tick(&mut hl).await.unwrap(), 0b110);
+    assert!(fades.is_done());
+    assert_eq!(fades.tick(&mut hl).await.unwrap(), 0);
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_frame() {
+    let brightness: Vec<u8> = (0..12).collect();
+    let colors: Vec<hl::Rgb> = (0..12).map(|i| hl::Rgb([i, 0x80, 0xFF - i])).collect();
+
+    let mut frame = brightness.clone();
+    frame.extend(colors.iter().flat_map(|c| c.0));
+
+    let expectations: Vec<Transaction> = frame
+        .chunks(16)
+        .enumerate()
+        .map(|(i, chunk)| regw(0x08 + (i * 16) as u8, chunk))
+        .collect();
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5036::new_with_buffer::<_, 17>(&mut i2c, Address::Address1);
+
+    // Writing the brightness and colors separately would take one transaction more.
+    assert_eq!(expectations.len(), 3);
+    assert_eq!(hl.chunks_needed(12) + hl.chunks_needed(36), 4);
+
+    hl.set_frame(&brightness, &colors).await.unwrap();
+    assert_eq!(
+        hl.set_frame(&brightness[1..], &colors).await,
+        Err(hl::Error::Index)
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_frame_lp5012() {
+    let brightness = [0x01, 0x02, 0x03, 0x04];
+    let colors = [0x10, 0x20, 0x30, 0x40].map(hl::Rgb::gray);
+
+    let mut frame = brightness.to_vec();
+    frame.extend(colors.iter().flat_map(|c| c.0));
+
+    // The brightness registers directly precede the color registers, written as a single block.
+    let expectations = [regw(0x07, &frame[..12]), regw(0x13, &frame[12..])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5012::new(&mut i2c, Address::Address1);
+    hl.set_frame(&brightness, &colors).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_frame_separate() {
+    let lp5018_regw = |register: u8, values: &[u8]| {
+        let mut expected = vec![register];
+        expected.extend_from_slice(values);
+        Transaction::write(hl::LP5018::i2c_address(Address::Address1), expected)
+    };
+
+    // Unused registers lie between the brightness and color registers of these variants.
+    let expectations = [
+        regw(0x07, &[0x01, 0x02, 0x03]),
+        regw(
+            0x0B,
+            &[0x10, 0x11, 0x12, 0x20, 0x21, 0x22, 0x30, 0x31, 0x32],
+        ),
+        lp5018_regw(0x07, &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06]),
+        lp5018_regw(0x0F, &[0x10; 12]),
+        lp5018_regw(0x1B, &[0x20; 6]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let colors = [0x10, 0x20, 0x30].map(|c| hl::Rgb([c, c + 1, c + 2]));
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.set_frame(&[0x01, 0x02, 0x03], &colors).await.unwrap();
+
+    let colors = [0x10, 0x10, 0x10, 0x10, 0x20, 0x20].map(hl::Rgb::gray);
+    let mut hl = hl::LP5018::new(&mut i2c, Address::Address1);
+    hl.set_frame(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06], &colors)
+        .await
+        .unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn frame_buffer_scroll() {
+    const X: hl::Rgb = hl::Rgb([0; 3]);
+    let [a, b, c] = [1, 2, 3].map(hl::Rgb::gray);
+
+    let mut frame = hl::FrameBuffer::<hl::LP5009>::new();
+    frame.copy_from_slice(&[a, b, c]);
+
+    frame.scroll(1, false);
+    assert_eq!(*frame, [X, a, b]);
+    frame.scroll(-2, false);
+    assert_eq!(*frame, [b, X, X]);
+
+    frame.copy_from_slice(&[a, b, c]);
+    frame.scroll(1, true);
+    assert_eq!(*frame, [c, a, b]);
+    frame.scroll(-1, true);
+    assert_eq!(*frame, [a, b, c]);
+    frame.scroll(-4, true);
+    assert_eq!(*frame, [b, c, a]);
+    frame.scroll(i8::MIN, false);
+    assert_eq!(*frame, [X, X, X]);
+
+    let mut i2c = Mock::new(&[regw(0x0B, &[0, 0, 0, 1, 1, 1, 0, 0, 0])]);
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    frame[2] = a;
+    frame.scroll(8, true);
+    frame.flush(&mut hl).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn broadcast_read() {
+    let mut i2c = Mock::new(&[]);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Broadcast);
+    assert_eq!(hl.config_report().await, Err(hl::Error::BroadcastRead));
+    assert_eq!(hl.diagnose_channel(0).await, Err(hl::Error::BroadcastRead));
+    #[cfg(feature = "smart-leds")]
+    assert!(matches!(
+        hl.read_frame_rgb8().await,
+        Err(hl::Error::BroadcastRead)
+    ));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn broadcast_configure() {
+    let broadcast = hl::LP5030::i2c_address(Address::Broadcast);
+    let mut i2c = Mock::new(&[Transaction::write(broadcast, vec![0x01, 0x2E])]);
+
+    // Written without reading back, as every device would respond.
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Broadcast);
+    hl.configure(&Config::max_brightness()).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn rgb_input_forms() {
+    const RED: [u8; 3] = [0xFF, 0x00, 0x00];
+
+    let mut expectations = vec![regw(0x2F, &RED); 4];
+    expectations.extend(regw_block(0x14, &RED.repeat(10)));
+    expectations.extend(regw_block(0x14, &RED.repeat(10)));
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_rgb(9, (0xFF, 0x00, 0x00)).await.unwrap();
+    hl.set_rgb(9, [0xFF, 0x00, 0x00]).await.unwrap();
+    hl.set_rgb(9, 0xFF0000u32).await.unwrap();
+    hl.set_rgb(9, hl::Rgb::RED).await.unwrap();
+    hl.set_progress(255, 0xFF0000u32, hl::Rgb::BLACK)
+        .await
+        .unwrap();
+    hl.set_progress(0, (0x00, 0x00, 0x00), [0xFF, 0x00, 0x00])
+        .await
+        .unwrap();
+
+    assert_eq!(hl::Rgb::from(0x12345678u32), hl::Rgb([0x34, 0x56, 0x78]));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_channel_bounds() {
+    let expectations = [regw(0x31, &[0xFF])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_channel(29, 0xFF).await.unwrap();
+    assert_eq!(hl.set_channel(30, 0xFF).await, Err(hl::Error::Index));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_rgb_bounds() {
+    let expectations = [regw(0x2F, &[0x01, 0x02, 0x03])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_rgb(9, (1, 2, 3)).await.unwrap();
+    assert_eq!(hl.set_rgb(10, (1, 2, 3)).await, Err(hl::Error::Index));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_rgb_brightness_bounds() {
+    let expectations = [regw(0x09, &[0x80])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.set_rgb_brightness(2, 0x80).await.unwrap();
+    assert_eq!(hl.set_rgb_brightness(3, 0x80).await, Err(hl::Error::Index));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn read_rgb() {
+    let expectations = [
+        regw(0x2F, &[0x01, 0x02, 0x03]),
+        regr(0x2F, &[0x01, 0x02, 0x03]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_rgb(9, (0x01, 0x02, 0x03)).await.unwrap();
+    assert_eq!(hl.read_rgb(9).await.unwrap(), hl::Rgb([0x01, 0x02, 0x03]));
+    assert_eq!(hl.read_rgb(10).await, Err(hl::Error::Index));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn read_channel() {
+    let expectations = [regw(0x19, &[0x7F]), regr(0x19, &[0x7F])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_channel(5, 0x7F).await.unwrap();
+    assert_eq!(hl.read_channel(5).await.unwrap(), 0x7F);
+    assert_eq!(hl.read_channel(30).await, Err(hl::Error::Index));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn read_rgb_brightness() {
+    let expectations = [regw(0x11, &[0x54]), regr(0x11, &[0x54])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_rgb_brightness(9, 0x54).await.unwrap();
+    assert_eq!(hl.read_rgb_brightness(9).await.unwrap(), 0x54);
+    assert_eq!(hl.read_rgb_brightness(10).await, Err(hl::Error::Index));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn reset() {
+    let expectations = [
+        regw(0x00, &[0x40]),
+        regw(0x38, &[0xFF]),
+        regw(0x00, &[0x40]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    let hl = hl.enable().await.unwrap();
+    let hl: hl::Driver<hl::LP5030, _, hl::marker::Standby> = hl.reset().await.unwrap();
+    hl.enable().await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn init() {
+    let expectations = [
+        regr(0x01, &[0x3C]),
+        regw(0x01, &[0x2E]),
+        regw(0x00, &[0x40]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    hl::LP5030::init(&mut i2c, Address::Address1, &Config::max_brightness())
+        .await
+        .unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn bank_mode() {
+    let expectations = [
+        regw(0x00, &[0x40]),
+        regw(0x02, &[0xFF, 0x0F]),
+        regw(0x02, &[0x00, 0x00]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    let hl = hl.enable().await.unwrap();
+    let hl: hl::Driver<hl::LP5036, _, hl::marker::Bank> = hl.into_bank_mode().await.unwrap();
+    hl.exit_bank_mode().await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn bank_mode_single_config() {
+    let expectations = [regw(0x00, &[0x40]), regw(0x02, &[0x0F])];
+    let mut i2c = Mock::new(&expectations);
+
+    let hl = hl::LP5012::new(&mut i2c, Address::Address1);
+    let hl = hl.enable().await.unwrap();
+    hl.into_bank_mode().await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn bank_color() {
+    let expectations = [
+        regw(0x00, &[0x40]),
+        regw(0x02, &[0xFF, 0x0F]),
+        regw(0x05, &[0x01, 0x02, 0x03]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    let mut hl = hl.enable().await.unwrap().into_bank_mode().await.unwrap();
+    hl.set_bank_color((0x01, 0x02, 0x03)).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn bank_brightness() {
+    let expectations = [
+        regw(0x00, &[0x40]),
+        regw(0x02, &[0x0F]),
+        regw(0x03, &[0x80]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let hl = hl::LP5012::new(&mut i2c, Address::Address1);
+    let mut hl = hl.enable().await.unwrap().into_bank_mode().await.unwrap();
+    hl.set_bank_brightness(0x80).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn led_bank_enable() {
+    let expectations = [
+        regr(0x02, &[0x01]),
+        regw(0x02, &[0x05]),
+        regr(0x03, &[0x0F]),
+        regw(0x03, &[0x0D]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    hl.set_led_bank_enable(2, true).await.unwrap();
+    hl.set_led_bank_enable(9, false).await.unwrap();
+    assert_eq!(
+        hl.set_led_bank_enable(12, true).await,
+        Err(hl::Error::Index)
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn read_bank_assignments() {
+    let mut i2c = Mock::new(&[regr(0x02, &[0x81, 0x08])]);
+
+    let mut hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    assert_eq!(hl.read_bank_assignments().await.unwrap(), 0b1000_1000_0001);
+
+    let mut i2c2 = Mock::new(&[regr(0x02, &[0x05])]);
+    let mut hl = hl::LP5012::new(&mut i2c2, Address::Address1);
+    assert_eq!(hl.read_bank_assignments().await.unwrap(), 0b0101);
+
+    i2c.done();
+    i2c2.done();
+}
+
+#[async_std::test]
+async fn off() {
+    let expectations = regw_block(0x14, &[0x00; 36]);
+    assert_eq!(expectations.len(), 3);
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    hl.off().await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn all_on() {
+    let mut expectations = vec![regw(0x0B, &[0x80; 9])];
+    expectations.extend(regw_block(0x14, &[0x80; 36]));
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.all_on(0x80).await.unwrap();
+    let mut hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    hl.all_on(0x80).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_all_rgb() {
+    let colors: Vec<hl::Rgb> = (0..10).map(|i| hl::Rgb([i, 0x10 + i, 0x20 + i])).collect();
+    let data: Vec<u8> = colors.iter().flat_map(|c| c.0).collect();
+
+    let mut expectations = vec![
+        regw(0x14, &data[..12]),
+        regw(0x20, &data[12..24]),
+        regw(0x2C, &data[24..]),
+    ];
+    expectations.push(regw(0x14, &data[..6]));
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_all_rgb(&colors).await.unwrap();
+    hl.set_all_rgb(&colors[..2]).await.unwrap();
+    assert_eq!(
+        hl.set_all_rgb(&[hl::Rgb::BLACK; 11]).await,
+        Err(hl::Error::Index)
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_rgb_range() {
+    let colors = [hl::Rgb::RED, hl::Rgb::GREEN, hl::Rgb::BLUE];
+    let expectations = [regw(
+        0x1D,
+        &[0xFF, 0x00, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x00, 0xFF],
+    )];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_rgb_range(3, &colors).await.unwrap();
+    assert_eq!(hl.set_rgb_range(8, &colors).await, Err(hl::Error::Index));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_channels() {
+    let values: Vec<u8> = (1..=15).collect();
+    let expectations = [regw(0x16, &values[..12]), regw(0x22, &values[12..])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    hl.set_channels(2, &values).await.unwrap();
+    assert_eq!(hl.set_channels(22, &values).await, Err(hl::Error::Index));
+
+    i2c.done();
+}
+
+#[test]
+fn config_builder() {
+    let config = Config::builder().power_save(false).build();
+    assert!(config.log_scale);
+    assert!(!config.power_save);
+    assert!(config.pwm_dithering);
+    assert_eq!(config.max_current, ll::MaxCurrentOption::Current25MA5);
+
+    let config = hl::ConfigBuilder::new()
+        .log_scale(false)
+        .pwm_dithering(false)
+        .max_current(ll::MaxCurrentOption::Current35MA)
+        .build();
+    assert!(!config.log_scale);
+    assert!(config.power_save);
+    assert!(!config.pwm_dithering);
+    assert_eq!(config.max_current, ll::MaxCurrentOption::Current35MA);
+}
+
+#[async_std::test]
+async fn read_config() {
+    let mut i2c = Mock::new(&[regr(0x01, &[0x3A])]);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    assert_eq!(
+        hl.read_config().await.unwrap(),
+        Config {
+            log_scale: true,
+            power_save: true,
+            pwm_dithering: false,
+            max_current: ll::MaxCurrentOption::Current35MA,
+            supply_voltage_mv: None,
+        }
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_log_scale() {
+    let expectations = [regr(0x01, &[0x3C]), regw(0x01, &[0x1C])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_log_scale(false).await.unwrap();
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Broadcast);
+    assert_eq!(hl.set_log_scale(false).await, Err(hl::Error::BroadcastRead));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_power_save() {
+    let expectations = [regr(0x01, &[0x3C]), regw(0x01, &[0x2C])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_power_save(false).await.unwrap();
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Broadcast);
+    assert_eq!(
+        hl.set_power_save(false).await,
+        Err(hl::Error::BroadcastRead)
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_pwm_dithering() {
+    let expectations = [regr(0x01, &[0x3C]), regw(0x01, &[0x38])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_pwm_dithering(false).await.unwrap();
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Broadcast);
+    assert_eq!(
+        hl.set_pwm_dithering(false).await,
+        Err(hl::Error::BroadcastRead)
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_max_current() {
+    let expectations = [regr(0x01, &[0x3C]), regw(0x01, &[0x3E])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_max_current(ll::MaxCurrentOption::Current35MA)
+        .await
+        .unwrap();
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Broadcast);
+    assert_eq!(
+        hl.set_max_current(ll::MaxCurrentOption::Current35MA).await,
+        Err(hl::Error::BroadcastRead)
+    );
+
+    i2c.done();
+}
+
+#[test]
+fn max_current_from_milliamps() {
+    use hl::MaxCurrentOption;
+
+    assert_eq!(
+        MaxCurrentOption::from_milliamps(25),
+        Some(MaxCurrentOption::Current25MA5)
+    );
+    assert_eq!(
+        MaxCurrentOption::from_milliamps(35),
+        Some(MaxCurrentOption::Current35MA)
+    );
+    assert_eq!(MaxCurrentOption::from_milliamps(30), None);
+    assert_eq!(MaxCurrentOption::from_milliamps(50), None);
+}
+
+#[async_std::test]
+async fn current_exceeds_supply() {
+    let expectations = [regr(0x01, &[0x3C]), regw(0x01, &[0x2E])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+
+    let config = Config {
+        supply_voltage_mv: Some(3000),
+        ..Config::max_brightness()
+    };
+    assert_eq!(
+        hl.configure(&config).await,
+        Err(hl::Error::CurrentExceedsSupply)
+    );
+
+    let config = Config {
+        supply_voltage_mv: Some(3300),
+        ..Config::max_brightness()
+    };
+    hl.configure(&config).await.unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn driver_counts() {
+    fn check<V: LP50xx>(led_count: u8, rgb_count: u8) {
+        let mut i2c = Mock::new(&[]);
+        let hl = V::new(&mut i2c, Address::Address1);
+        assert_eq!(hl.led_count(), led_count);
+        assert_eq!(hl.rgb_count(), rgb_count);
+        i2c.done();
+    }
+
+    check::<hl::LP5009>(9, 3);
+    check::<hl::LP5012>(12, 4);
+    check::<hl::LP5018>(18, 6);
+    check::<hl::LP5024>(24, 8);
+    check::<hl::LP5030>(30, 10);
+    check::<hl::LP5036>(36, 12);
+}
+
+#[async_std::test]
+async fn into_inner() {
+    let i2c = Mock::new(&[regw(0x00, &[0x40])]);
+
+    let hl = hl::LP5030::new(i2c, Address::Address1);
+    let hl = hl.enable().await.unwrap();
+    let mut i2c = hl.into_inner();
+
+    i2c.done();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn rgb_serde() {
+    let json = serde_json::to_string(&hl::Rgb([0x01, 0x80, 0xFF])).unwrap();
+    assert_eq!(json, "[1,128,255]");
+    assert_eq!(
+        serde_json::from_str::<hl::Rgb>(&json).unwrap(),
+        hl::Rgb([0x01, 0x80, 0xFF])
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn config_serde() {
+    let config = Config {
+        supply_voltage_mv: Some(3300),
+        ..Config::max_brightness()
+    };
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(
+        json,
+        r#"{"log_scale":true,"power_save":false,"pwm_dithering":true,"max_current":"Current35MA","supply_voltage_mv":3300}"#
+    );
+    assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+
+    assert!(serde_json::from_str::<hl::MaxCurrentOption>(r#""Current50MA""#).is_err());
+}
+
+#[cfg(feature = "defmt-03")]
+#[test]
+fn defmt_format() {
+    fn assert_format<F: defmt::Format>() {}
+
+    assert_format::<Config>();
+    assert_format::<Address>();
+    assert_format::<hl::MaxCurrentOption>();
+    assert_format::<hl::Rgb>();
+    assert_format::<hl::Error<()>>();
+}
+
+#[test]
+fn error_display() {
+    assert_eq!(hl::Error::Interface(()).to_string(), "I2C interface error");
+    assert_eq!(
+        hl::Error::<()>::Index.to_string(),
+        "LED/RGB index out of range"
+    );
+    assert_eq!(
+        hl::Error::<()>::BroadcastRead.to_string(),
+        "read through the broadcast address"
+    );
+    assert_eq!(
+        hl::Error::<()>::CurrentExceedsSupply.to_string(),
+        "maximum current not valid for the supply voltage"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn error_source() {
+    use std::error::Error;
+
+    #[derive(Debug)]
+    struct BusError;
+
+    impl std::fmt::Display for BusError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("bus error")
+        }
+    }
+
+    impl Error for BusError {}
+
+    let error: Box<dyn Error> = Box::new(hl::Error::Interface(BusError));
+    assert_eq!(error.to_string(), "I2C interface error");
+    assert_eq!(error.source().unwrap().to_string(), "bus error");
+
+    assert!(hl::Error::<BusError>::Index.source().is_none());
+}
+
+#[cfg(feature = "smart-leds")]
+#[async_std::test]
+async fn smart_leds_write() {
+    use smart_leds_trait::{SmartLedsWriteAsync, RGB8};
+
+    let expectations = [
+        regw(0x00, &[0x40]),
+        regw(0x0B, &[0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    let mut hl = hl.enable().await.unwrap();
+    hl.write([RGB8::new(0xFF, 0, 0), RGB8::new(0, 0, 0xFF)])
+        .await
+        .unwrap();
+    assert_eq!(hl.write([RGB8::default(); 4]).await, Err(hl::Error::Index));
+
+    i2c.done();
+}
+
+#[cfg(feature = "rgb")]
+#[test]
+fn rgb_crate_conversion() {
+    let color = rgb::RGB8 {
+        r: 0x01,
+        g: 0x80,
+        b: 0xFF,
+    };
+    assert_eq!(hl::Rgb::from(color), hl::Rgb([0x01, 0x80, 0xFF]));
+    assert_eq!(rgb::RGB8::from(hl::Rgb::from(color)), color);
+}
+
+#[test]
+fn hsv_to_rgb() {
+    let rgb = |h| hl::Rgb::from(hl::Hsv { h, s: 255, v: 255 });
+
+    // Start of each sextant.
+    assert_eq!(rgb(0), hl::Rgb([255, 0, 0]));
+    assert_eq!(rgb(43), hl::Rgb([253, 255, 0]));
+    assert_eq!(rgb(86), hl::Rgb([0, 255, 4]));
+    assert_eq!(rgb(128), hl::Rgb([0, 255, 255]));
+    assert_eq!(rgb(171), hl::Rgb([2, 0, 255]));
+    assert_eq!(rgb(214), hl::Rgb([255, 0, 251]));
+    assert_eq!(rgb(255), hl::Rgb([255, 0, 5]));
+
+    assert_eq!(
+        hl::Rgb::from(hl::Hsv {
+            h: 100,
+            s: 0,
+            v: 77
+        }),
+        hl::Rgb::gray(77)
+    );
+}
+
+#[async_std::test]
+async fn set_rgb_gamma() {
+    let expectations = [regw(0x2F, &[56, 12, 137])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    hl.set_rgb_gamma(9, (128, 64, 192)).await.unwrap();
+    assert_eq!(
+        hl.set_rgb_gamma(10, (128, 64, 192)).await,
+        Err(hl::Error::Index)
+    );
+
+    assert_eq!(hl::GAMMA[0], 0);
+    assert_eq!(hl::GAMMA[255], 255);
+
+    i2c.done();
+}
+
+#[test]
+fn rgb_scale() {
+    let color = hl::Rgb([0xFF, 0x80, 0x01]);
+
+    assert_eq!(color.scale(0), hl::Rgb::BLACK);
+    assert_eq!(color.scale(255), color);
+    assert_eq!(color.scale(128), hl::Rgb([0x80, 0x40, 0x01]));
+}
+
+#[test]
+fn rgb_lerp() {
+    let from = hl::Rgb([0x00, 0x00, 0xFF]);
+    let to = hl::Rgb([0xFF, 0x01, 0x00]);
+
+    assert_eq!(from.lerp(to, 0), from);
+    assert_eq!(from.lerp(to, 255), to);
+    assert_eq!(from.lerp(to, 128), hl::Rgb([0x80, 0x01, 0x7F]));
+    assert_eq!(to.lerp(from, 128), hl::Rgb([0x7F, 0x00, 0x80]));
+}
+
+#[test]
+fn rgb_from_hex() {
+    const ORANGE: hl::Rgb = hl::Rgb::from_hex(0xFF8800);
+
+    assert_eq!(ORANGE, hl::Rgb([0xFF, 0x88, 0x00]));
+    assert_eq!(hl::Rgb::from_hex(0xAB123456), hl::Rgb([0x12, 0x34, 0x56]));
+}
+
+#[test]
+fn rgb_from_kelvin() {
+    // Warm white: red dominates, with some blue.
+    let warm = hl::Rgb::from_kelvin(2700);
+    assert_eq!(warm, hl::Rgb([255, 166, 86]));
+    assert!(warm[0] > warm[1] && warm[1] > warm[2]);
+
+    // Neutral white: all channels nearly equal.
+    let neutral = hl::Rgb::from_kelvin(6500);
+    assert_eq!(neutral, hl::Rgb([255, 254, 250]));
+
+    // Cold white: blue dominates.
+    let cold = hl::Rgb::from_kelvin(10000);
+    assert!(cold[2] > cold[1] && cold[1] > cold[0]);
+
+    assert_eq!(hl::Rgb::from_kelvin(0), hl::Rgb::from_kelvin(1000));
+    assert_eq!(hl::Rgb::from_kelvin(u16::MAX), hl::Rgb([191, 211, 255]));
+}
+
+#[async_std::test]
+async fn frame_buffer_commit() {
+    let [a, b] = [1, 2].map(hl::Rgb::gray);
+
+    let mut expectations = regw_block(0x14, &[0; 30]);
+    expectations.extend([
+        regw(0x17, &[1, 1, 1]),
+        regw(0x29, &[2, 2, 2]),
+        regw(0x1D, &[1, 1, 1, 2, 2, 2]),
+    ]);
+    let mut i2c = Mock::new(&expectations);
+    let mut hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    let mut frame = hl::FrameBuffer::<hl::LP5030>::new();
+
+    // A new frame buffer writes all RGB LEDs.
+    frame.commit(&mut hl).await.unwrap();
+
+    // Nothing changed.
+    frame.commit(&mut hl).await.unwrap();
+
+    frame.set(1, a);
+    frame.set(7, b);
+    frame.commit(&mut hl).await.unwrap();
+
+    // Setting the same color is not a change.
+    frame.set(1, a);
+    frame.set(3, a);
+    frame.set(4, b);
+    frame.commit(&mut hl).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn double_buffer_present() {
+    let a = hl::Rgb::gray(1);
+
+    let mut i2c = Mock::new(&[
+        regw(0x0B, &[0, 0, 0, 1, 1, 1, 0, 0, 0]),
+        regw(0x0B, &[0, 0, 0, 0, 0, 0, 0, 0, 0]),
+    ]);
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    let mut frames = hl::DoubleBuffer::<hl::LP5009>::new();
+
+    frames.set(1, a);
+    assert_eq!(frames.front(), [hl::Rgb::BLACK; 3]);
+
+    // The full frame is written, even though only a single RGB LED changed.
+    frames.present(&mut hl).await.unwrap();
+    assert_eq!(frames.front(), [hl::Rgb::BLACK, a, hl::Rgb::BLACK]);
+    assert_eq!(**frames, [hl::Rgb::BLACK; 3]);
+
+    frames.present(&mut hl).await.unwrap();
+
+    i2c.done();
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn breathe() {
+    use embedded_hal_mock::eh1::delay::{self, CheckedDelay};
+
+    let brightness: Vec<u8> = (0..=16)
+        .chain((0..16).rev())
+        .map(|i| (i * 255 / 16) as u8)
+        .collect();
+    assert!(brightness[..17].is_sorted());
+    assert!(brightness[16..].iter().rev().is_sorted());
+    assert_eq!((brightness[0], brightness[16], brightness[32]), (0, 255, 0));
+
+    let mut expectations = vec![regw(0x0E, &[0xFF, 0x80, 0x00])];
+    expectations.extend(brightness.iter().map(|b| regw(0x08, &[*b])));
+    let mut i2c = Mock::new(&expectations);
+    let mut delay = CheckedDelay::new(&vec![delay::Transaction::delay_us(31_250); 32]);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.breathe(1, hl::Rgb::from_hex(0xFF8000), 1000, &mut delay)
+        .await
+        .unwrap();
+    assert_eq!(
+        hl.breathe(3, hl::Rgb::WHITE, 1000, &mut delay).await,
+        Err(hl::Error::Index)
+    );
+
+    i2c.done();
+    delay.done();
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn rainbow_step() {
+    let color = |h| hl::Rgb::from(hl::Hsv { h, s: 255, v: 255 });
+    let frame = |hues: [u8; 4]| hues.map(|h| color(h).0).concat();
+
+    let expectations = [
+        regw(0x0B, &frame([0, 192, 128, 64])),
+        regw(0x0B, &frame([64, 0, 192, 128])),
+        regw(0x0B, &frame([65, 1, 193, 129])),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5012::new(&mut i2c, Address::Address1);
+    hl.rainbow_step(0).await.unwrap();
+    // Advancing by a quarter of the hue range moves the pattern by one RGB LED.
+    hl.rainbow_step(64).await.unwrap();
+    hl.rainbow_step(65).await.unwrap();
+
+    i2c.done();
+}
+
+#[cfg(feature = "animations")]
+#[async_std::test]
+async fn crossfade() {
+    use embedded_hal_mock::eh1::delay::{self, CheckedDelay};
+
+    let from = [hl::Rgb::BLACK, hl::Rgb::WHITE, hl::Rgb::RED];
+    let to = [hl::Rgb::WHITE, hl::Rgb::BLACK, hl::Rgb::BLUE];
+
+    let expectations = [
+        regw(
+            0x0B,
+            &[0x80, 0x80, 0x80, 0x7F, 0x7F, 0x7F, 0x7F, 0x00, 0x80],
+        ),
+        regw(
+            0x0B,
+            &[0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF],
+        ),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut delay = CheckedDelay::new(&[
+        delay::Transaction::delay_ms(20),
+        delay::Transaction::delay_ms(20),
+    ]);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.crossfade(&from, &to, 2, 20, &mut delay).await.unwrap();
+    assert_eq!(
+        hl.crossfade(&from[..2], &to[..2], 2, 20, &mut delay).await,
+        Err(hl::Error::Index)
+    );
+
+    i2c.done();
+    delay.done();
+}
+
+#[async_std::test]
+async fn full_frame_chunks() {
+    let colors: Vec<hl::Rgb> = (0..12).map(hl::Rgb::gray).collect();
+    let bytes: Vec<u8> = (0..12).flat_map(|i| [i; 3]).collect();
+
+    // A full frame of 36 bytes is split at RGB LED boundaries into three transactions of 12 bytes.
+    let expectations = [
+        regw(0x14, &bytes[..12]),
+        regw(0x20, &bytes[12..24]),
+        regw(0x2C, &bytes[24..]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    assert_eq!(hl.chunks_needed(36), 3);
+    hl.set_all_rgb(&colors).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_rgb_full() {
+    // The brightness is written before the color.
+    let expectations = [regw(0x09, &[0x80]), regw(0x11, &[0x01, 0x02, 0x03])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.set_rgb_full(2, 0x80, (0x01, 0x02, 0x03)).await.unwrap();
+    assert_eq!(
+        hl.set_rgb_full(3, 0x80, (0x01, 0x02, 0x03)).await,
+        Err(hl::Error::Index)
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn set_all_brightness_length() {
+    let expectations = [regw(0x07, &[0x40; 3]), regw(0x08, &[0x40; 12])];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = hl::LP5009::new(&mut i2c, Address::Address1);
+    hl.set_all_brightness(0x40).await.unwrap();
+
+    let mut hl = hl::LP5036::new(&mut i2c, Address::Address1);
+    hl.set_all_brightness(0x40).await.unwrap();
+
+    i2c.done();
+}
+
+#[test]
+fn address_from_pins() {
+    use hl::PinLevel::{Gnd, Vcc};
+
+    assert_eq!(Address::from_pins(Gnd, Gnd), Address::Address0);
+    assert_eq!(Address::from_pins(Vcc, Gnd), Address::Address1);
+    assert_eq!(Address::from_pins(Gnd, Vcc), Address::Address2);
+    assert_eq!(Address::from_pins(Vcc, Vcc), Address::Address3);
+}
+
+#[test]
+fn i2c_address() {
+    const LP5030_ADDRESS1: u8 = Address::Address1.i2c_address::<hl::LP5030>();
+    assert_eq!(LP5030_ADDRESS1, ADDRESS);
+
+    assert_eq!(hl::LP5024::i2c_address(Address::Address0), 0x28);
+    assert_eq!(hl::LP5024::i2c_address(Address::Address3), 0x2B);
+    assert_eq!(hl::LP5024::i2c_address(Address::Broadcast), 0x3C);
+    assert_eq!(hl::LP5030::i2c_address(Address::Address0), 0x30);
+    assert_eq!(hl::LP5030::i2c_address(Address::Address2), 0x32);
+    assert_eq!(hl::LP5030::i2c_address(Address::Broadcast), 0x1C);
+}
+
+#[test]
+fn blocking() {
+    use crate::blocking;
+
+    let expectations = [
+        regr(0x01, &[0x3C]),
+        regw(0x01, &[0x2E]),
+        regw(0x00, &[0x40]),
+        regw(0x2F, &[0x01, 0x02, 0x03]),
+        regr(0x2F, &[0x01, 0x02, 0x03]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = blocking::Driver::<hl::LP5030, _, _>::new(&mut i2c, Address::Address1);
+    hl.configure(&Config::max_brightness()).unwrap();
+
+    let mut hl = hl.enable().unwrap();
+    hl.set_rgb(9, (0x01, 0x02, 0x03)).unwrap();
+    assert_eq!(hl.set_rgb(10, (0x01, 0x02, 0x03)), Err(hl::Error::Index));
+    assert_eq!(hl.read_rgb(9), Ok(hl::Rgb([0x01, 0x02, 0x03])));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn disable_and_wait() {
+    use embedded_hal_mock::eh1::delay::{self, CheckedDelay};
+
+    let expectations = [regw(0x00, &[0x40]), regw(0x00, &[0x00])];
+    let mut i2c = Mock::new(&expectations);
+    let mut delay = CheckedDelay::new(&[delay::Transaction::delay_us(1000)]);
+
+    let hl = hl::LP5030::new(&mut i2c, Address::Address1);
+    let hl = hl.enable().await.unwrap();
+    hl.disable_and_wait(&mut delay).await.unwrap();
+
+    i2c.done();
+    delay.done();
+}
+
+#[test]
+fn frame_buffer_try_set() {
+    let mut frame = hl::FrameBuffer::<hl::LP5009>::new();
+
+    assert_eq!(frame.try_set(2, hl::Rgb::WHITE), Ok(()));
+    assert_eq!(frame[2], hl::Rgb::WHITE);
+    assert_eq!(frame.try_set(3, hl::Rgb::WHITE), Err(hl::Error::Index));
+}
+
+#[test]
+#[should_panic]
+fn frame_buffer_set_out_of_range() {
+    let mut frame = hl::FrameBuffer::<hl::LP5009>::new();
+    frame.set(3, hl::Rgb::WHITE);
+}
@@ -1,6 +1,10 @@
-#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
 
+pub mod blocking;
 mod hl;
 mod ll;
+pub mod mux;
+#[cfg(feature = "std")]
+pub mod recording;
 
 pub use hl::*;
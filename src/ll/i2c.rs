@@ -3,25 +3,65 @@ use embedded_hal_async::i2c::I2c;
 
 // Max write size is for LP5036,
 // where for 12 LEDs the brightness can be configured at once.
-const MAX_WRITE_SIZE: usize = 13;
+// Used as the default scratch buffer size.
+pub const MAX_WRITE_SIZE: usize = 13;
 
 use crate::ll::DeviceError;
 
-pub struct DeviceInterface<I2C: I2c> {
+/// Interface to the device with the default scratch buffer size.
+pub type DeviceInterface<I2C> = BufferedDeviceInterface<I2C, MAX_WRITE_SIZE>;
+
+/// Interface to the device, using a scratch buffer of `N` bytes (including the register address) for writes.
+pub struct BufferedDeviceInterface<I2C: I2c, const N: usize> {
     i2c: I2C,
     address: u8,
+    max_transfer: usize,
 }
 
-impl<I2C: I2c> DeviceInterface<I2C> {
+impl<I2C: I2c, const N: usize> BufferedDeviceInterface<I2C, N> {
+    /// Default maximum number of bytes in a single I2C write, including the register address.
+    pub const MAX_I2C_TRANSFER: usize = N;
+
     /// Construct a new instance of the device.
     ///
     /// I2C max frequency 400kHz.
     pub const fn new(i2c: I2C, address: u8) -> Self {
-        Self { i2c, address }
+        const {
+            assert!(
+                N >= 2,
+                "scratch buffer must fit the register address and data"
+            )
+        };
+
+        Self {
+            i2c,
+            address,
+            max_transfer: Self::MAX_I2C_TRANSFER,
+        }
+    }
+
+    /// Limit the number of bytes in a single I2C write, including the register address.
+    ///
+    /// Buffer writes are cut short to respect this limit, for I2C controllers with small FIFOs.
+    /// Clamped between 2 and [Self::MAX_I2C_TRANSFER].
+    pub fn set_max_transfer(&mut self, max_transfer: usize) {
+        self.max_transfer = max_transfer.clamp(2, Self::MAX_I2C_TRANSFER);
+    }
+
+    /// Maximum number of bytes in a single I2C write, including the register address.
+    pub fn max_transfer(&self) -> usize {
+        self.max_transfer
+    }
+
+    /// Consume the interface, returning the underlying I2C peripheral.
+    pub fn into_inner(self) -> I2C {
+        self.i2c
     }
 }
 
-impl<I2C: I2c> device_driver::AsyncRegisterInterface for DeviceInterface<I2C> {
+impl<I2C: I2c, const N: usize> device_driver::AsyncRegisterInterface
+    for BufferedDeviceInterface<I2C, N>
+{
     type Error = DeviceError<I2C::Error>;
 
     type AddressType = u8;
@@ -32,7 +72,7 @@ impl<I2C: I2c> device_driver::AsyncRegisterInterface for DeviceInterface<I2C> {
         _size_bits: u32,
         data: &[u8],
     ) -> Result<(), Self::Error> {
-        let mut vec = heapless::Vec::<u8, MAX_WRITE_SIZE>::new();
+        let mut vec = heapless::Vec::<u8, N>::new();
         vec.push(address).map_err(|_| DeviceError::BufferTooSmall)?;
         vec.extend_from_slice(data)
             .map_err(|_| DeviceError::BufferTooSmall)?;
@@ -49,11 +89,15 @@ impl<I2C: I2c> device_driver::AsyncRegisterInterface for DeviceInterface<I2C> {
     }
 }
 
-impl<I2C: I2c> device_driver::BufferInterfaceError for DeviceInterface<I2C> {
+impl<I2C: I2c, const N: usize> device_driver::BufferInterfaceError
+    for BufferedDeviceInterface<I2C, N>
+{
     type Error = DeviceError<I2C::Error>;
 }
 
-impl<I2C: I2c> device_driver::AsyncBufferInterface for DeviceInterface<I2C> {
+impl<I2C: I2c, const N: usize> device_driver::AsyncBufferInterface
+    for BufferedDeviceInterface<I2C, N>
+{
     type AddressType = u8;
 
     async fn write(
@@ -61,8 +105,11 @@ impl<I2C: I2c> device_driver::AsyncBufferInterface for DeviceInterface<I2C> {
         address: Self::AddressType,
         buf: &[u8],
     ) -> Result<usize, Self::Error> {
-        self.write_register(address, buf.len() as u32, buf).await?;
-        Ok(buf.len())
+        // Reserve one byte for the register address.
+        let len = buf.len().min(self.max_transfer - 1);
+        self.write_register(address, len as u32, &buf[..len])
+            .await?;
+        Ok(len)
     }
 
     async fn flush(&mut self, address: Self::AddressType) -> Result<(), Self::Error> {
@@ -71,12 +118,12 @@ impl<I2C: I2c> device_driver::AsyncBufferInterface for DeviceInterface<I2C> {
         Ok(())
     }
 
-    #[allow(unused)]
     async fn read(
         &mut self,
         address: Self::AddressType,
         buf: &mut [u8],
     ) -> Result<usize, Self::Error> {
-        unimplemented!()
+        self.read_register(address, buf.len() as u32, buf).await?;
+        Ok(buf.len())
     }
 }
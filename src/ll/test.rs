@@ -2,6 +2,7 @@ use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
 
 use crate::ll;
 
+#[allow(clippy::unusual_byte_groupings)]
 const ADDRESS: u8 = 0b0110_000;
 
 fn regw(register: u8, values: &[u8]) -> Transaction {
@@ -36,3 +37,19 @@ async fn base_config() {
 
     i2c.done();
 }
+
+#[async_std::test]
+async fn buffer_read() {
+    use device_driver::AsyncBufferInterface;
+
+    let expectations = [regr(0x14, &[0x01, 0x02, 0x03])];
+
+    let mut i2c = Mock::new(&expectations);
+
+    let mut interface = ll::i2c::DeviceInterface::new(&mut i2c, ADDRESS);
+    let mut buf = [0u8; 3];
+    assert_eq!(interface.read(0x14, &mut buf).await.unwrap(), 3);
+    assert_eq!(buf, [0x01, 0x02, 0x03]);
+
+    i2c.done();
+}
@@ -0,0 +1,95 @@
+use core::{fmt::Debug, marker::PhantomData};
+
+use embedded_hal_async::i2c::{self, ErrorKind, ErrorType, I2c, Operation};
+
+/// Error of a [MuxedInterface].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum MuxError<T, E> {
+    /// The underlying I2C interface returned an error.
+    Interface(T),
+    /// Selecting the mux channel failed.
+    Select(E),
+}
+
+impl<T: i2c::Error, E: Debug> i2c::Error for MuxError<T, E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            MuxError::Interface(e) => e.kind(),
+            MuxError::Select(_) => ErrorKind::Other,
+        }
+    }
+}
+
+/// I2C interface for a device placed behind an I2C mux, like the TCA9548A.
+///
+/// Calls `select` before every transaction to select the mux channel of the device,
+/// such that the driver works transparently behind a mux shared with other devices.
+/// The callback is synchronous: select the channel using for example a blocking I2C handle or GPIO.
+pub struct MuxedInterface<T, F, E> {
+    i2c: T,
+    select: F,
+    error: PhantomData<fn() -> E>,
+}
+
+impl<T: I2c, F: FnMut() -> Result<(), E>, E: Debug> MuxedInterface<T, F, E> {
+    /// Construct an interface selecting the mux channel for the device using `select`.
+    pub fn new(i2c: T, select: F) -> Self {
+        Self {
+            i2c,
+            select,
+            error: PhantomData,
+        }
+    }
+
+    fn select(&mut self) -> Result<(), MuxError<T::Error, E>> {
+        (self.select)().map_err(MuxError::Select)
+    }
+}
+
+impl<T: I2c, F: FnMut() -> Result<(), E>, E: Debug> ErrorType for MuxedInterface<T, F, E> {
+    type Error = MuxError<T::Error, E>;
+}
+
+impl<T: I2c, F: FnMut() -> Result<(), E>, E: Debug> I2c for MuxedInterface<T, F, E> {
+    async fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.select()?;
+        self.i2c
+            .read(address, read)
+            .await
+            .map_err(MuxError::Interface)
+    }
+
+    async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        self.select()?;
+        self.i2c
+            .write(address, write)
+            .await
+            .map_err(MuxError::Interface)
+    }
+
+    async fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.select()?;
+        self.i2c
+            .write_read(address, write, read)
+            .await
+            .map_err(MuxError::Interface)
+    }
+
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.select()?;
+        self.i2c
+            .transaction(address, operations)
+            .await
+            .map_err(MuxError::Interface)
+    }
+}
@@ -0,0 +1,68 @@
+use core::convert::Infallible;
+
+use embedded_hal_async::i2c::{ErrorType, I2c, Operation};
+
+/// A single I2C operation recorded by [RecordingInterface].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Record {
+    /// Bytes written to the device at `address`, starting with the register address.
+    Write { address: u8, bytes: Vec<u8> },
+    /// Number of bytes read from the device at `address`.
+    Read { address: u8, len: usize },
+}
+
+/// I2C interface recording all operations instead of communicating with a device.
+///
+/// Useful for testing application code against the driver by asserting the produced bus traffic,
+/// without specifying every transaction up front.
+/// Writes always succeed, and reads always return zeroes.
+#[derive(Debug, Default)]
+pub struct RecordingInterface {
+    log: Vec<Record>,
+}
+
+impl RecordingInterface {
+    /// Construct an interface with an empty log.
+    pub const fn new() -> Self {
+        Self { log: Vec::new() }
+    }
+
+    /// All operations recorded so far, in order.
+    pub fn log(&self) -> &[Record] {
+        &self.log
+    }
+
+    /// Take all operations recorded so far, clearing the log.
+    pub fn take_log(&mut self) -> Vec<Record> {
+        core::mem::take(&mut self.log)
+    }
+}
+
+impl ErrorType for RecordingInterface {
+    type Error = Infallible;
+}
+
+impl I2c for RecordingInterface {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => self.log.push(Record::Write {
+                    address,
+                    bytes: bytes.to_vec(),
+                }),
+                Operation::Read(buf) => {
+                    buf.fill(0);
+                    self.log.push(Record::Read {
+                        address,
+                        len: buf.len(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}